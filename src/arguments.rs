@@ -27,6 +27,243 @@ impl fmt::Display for Decoration {
     }
 }
 
+/// The characters used for drawing table borders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Border {
+    pub top_left: char,
+    pub top_tee: char,
+    pub top_right: char,
+    pub header_left: char,
+    pub header_cross: char,
+    pub header_right: char,
+    pub bottom_left: char,
+    pub bottom_tee: char,
+    pub bottom_right: char,
+    pub horizontal: char,
+    pub header: char,
+    pub vertical: char,
+    /// Put a space on either side of each cell
+    pub padding: bool,
+}
+
+impl Border {
+    fn from_chars(chars: &str, padding: bool) -> Self {
+        let c: Vec<char> = chars.chars().collect();
+        Border {
+            top_left: c[0],
+            top_tee: c[1],
+            top_right: c[2],
+            header_left: c[3],
+            header_cross: c[4],
+            header_right: c[5],
+            bottom_left: c[6],
+            bottom_tee: c[7],
+            bottom_right: c[8],
+            horizontal: c[9],
+            header: c[10],
+            vertical: c[11],
+            padding,
+        }
+    }
+}
+
+impl FromStr for Border {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let n = s.chars().count();
+        if n != 12 {
+            return Err(format!("Expected 12 characters, got {}", n));
+        }
+        Ok(Border::from_chars(s, false))
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Default, Debug, PartialEq, Eq)]
+pub enum Theme {
+    #[default]
+    Single,
+    Rounded,
+    Double,
+    Heavy,
+    Dashed,
+    #[clap(alias = "md")]
+    Markdown,
+    Psql,
+}
+
+impl Theme {
+    /// The border characters of the theme, or of its ASCII fallback.
+    pub fn border(&self, ascii: bool) -> Border {
+        match (self, ascii) {
+            (Theme::Single, false) => Border::from_chars("┌┬┐├┼┤└┴┘──│", false),
+            (Theme::Rounded, false) => Border::from_chars("╭┬╮├┼┤╰┴╯──│", false),
+            (Theme::Double, false) => Border::from_chars("╔╦╗╠╬╣╚╩╝══║", false),
+            (Theme::Heavy, false) => Border::from_chars("┏┳┓┣╋┫┗┻┛━━┃", false),
+            (Theme::Dashed, false) => Border::from_chars("┌┬┐├┼┤└┴┘┄┄┆", false),
+            (Theme::Single, true) => Border::from_chars("+++++++++--|", false),
+            (Theme::Rounded, true) => Border::from_chars("...+++'''--|", false),
+            (Theme::Double, true) => Border::from_chars("+++++++++==|", false),
+            (Theme::Heavy, true) => Border::from_chars("#########==|", false),
+            (Theme::Dashed, true) => Border::from_chars("+++++++++--:", false),
+            (Theme::Markdown, _) => Border::from_chars("|||||||||--|", true),
+            (Theme::Psql, _) => Border::from_chars("+++++++++--|", true),
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            Theme::Single => write!(f, "single"),
+            Theme::Rounded => write!(f, "rounded"),
+            Theme::Double => write!(f, "double"),
+            Theme::Heavy => write!(f, "heavy"),
+            Theme::Dashed => write!(f, "dashed"),
+            Theme::Markdown => write!(f, "markdown"),
+            Theme::Psql => write!(f, "psql"),
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Default, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    #[clap(alias = "t")]
+    Table,
+    #[clap(alias = "md")]
+    Markdown,
+    Html,
+    Csv,
+    Tsv,
+    Json,
+    #[clap(alias = "jsonl")]
+    Ndjson,
+    #[clap(alias = "tex")]
+    Latex,
+    #[clap(alias = "rst")]
+    RstGrid,
+    RstSimple,
+    #[clap(alias = "adoc")]
+    Asciidoc,
+    Org,
+    Sql,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            OutputFormat::Table => write!(f, "table"),
+            OutputFormat::Markdown => write!(f, "markdown"),
+            OutputFormat::Html => write!(f, "html"),
+            OutputFormat::Csv => write!(f, "csv"),
+            OutputFormat::Tsv => write!(f, "tsv"),
+            OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Ndjson => write!(f, "ndjson"),
+            OutputFormat::Latex => write!(f, "latex"),
+            OutputFormat::RstGrid => write!(f, "rst-grid"),
+            OutputFormat::RstSimple => write!(f, "rst-simple"),
+            OutputFormat::Asciidoc => write!(f, "asciidoc"),
+            OutputFormat::Org => write!(f, "org"),
+            OutputFormat::Sql => write!(f, "sql"),
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Default, Debug, PartialEq, Eq)]
+pub enum InputFormat {
+    #[default]
+    #[clap(alias = "d")]
+    Delimited,
+    #[clap(alias = "j")]
+    Json,
+    #[clap(alias = "jsonl")]
+    Ndjson,
+    #[clap(alias = "md")]
+    Markdown,
+    #[clap(alias = "fw")]
+    FixedWidth,
+    Logfmt,
+    #[clap(alias = "r")]
+    Records,
+}
+
+impl fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            InputFormat::Delimited => write!(f, "delimited"),
+            InputFormat::Json => write!(f, "json"),
+            InputFormat::Ndjson => write!(f, "ndjson"),
+            InputFormat::Markdown => write!(f, "markdown"),
+            InputFormat::FixedWidth => write!(f, "fixed-width"),
+            InputFormat::Logfmt => write!(f, "logfmt"),
+            InputFormat::Records => write!(f, "records"),
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Default, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    #[clap(name = "utf-8", alias = "utf8")]
+    Utf8,
+    #[clap(name = "utf-8-lossy", alias = "utf8-lossy")]
+    Utf8Lossy,
+    #[clap(alias = "iso-8859-1")]
+    Latin1,
+    #[clap(name = "utf-16", alias = "utf16")]
+    Utf16,
+    #[clap(name = "utf-16le", alias = "utf16le")]
+    Utf16le,
+    #[clap(name = "utf-16be", alias = "utf16be")]
+    Utf16be,
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            Encoding::Utf8 => write!(f, "utf-8"),
+            Encoding::Utf8Lossy => write!(f, "utf-8-lossy"),
+            Encoding::Latin1 => write!(f, "latin1"),
+            Encoding::Utf16 => write!(f, "utf-16"),
+            Encoding::Utf16le => write!(f, "utf-16le"),
+            Encoding::Utf16be => write!(f, "utf-16be"),
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Default, Debug, PartialEq, Eq)]
+pub enum JsonArrays {
+    #[default]
+    Join,
+    Index,
+}
+
+impl fmt::Display for JsonArrays {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            JsonArrays::Join => write!(f, "join"),
+            JsonArrays::Index => write!(f, "index"),
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Default, Debug, PartialEq, Eq)]
+pub enum Unmatched {
+    #[default]
+    Drop,
+    Report,
+}
+
+impl fmt::Display for Unmatched {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            Unmatched::Drop => write!(f, "drop"),
+            Unmatched::Report => write!(f, "report"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Alignment {
     Left,
@@ -306,6 +543,60 @@ pub struct Args {
     )]
     pub fixed_width: Option<Vec<WidthSpecifier>>,
 
+    /// How the output should be formatted
+    ///
+    /// table       A table for viewing in the terminal
+    /// markdown    A GitHub flavored markdown table, the column alignment is
+    ///             taken from --layout
+    /// html        An HTML table, the column alignment is taken from --layout
+    /// csv         Comma separated values, quoted according to RFC 4180
+    /// tsv         Tab separated values, quoted like csv
+    /// json        An array of objects, keyed by the column headers
+    /// ndjson      One object per line (JSON Lines), keyed by the column
+    ///             headers
+    /// latex       A LaTeX tabular environment, the column specification is
+    ///             taken from --layout and the rules from --decoration
+    /// rst-grid    A reStructuredText grid table
+    /// rst-simple  A reStructuredText simple table
+    /// asciidoc    An AsciiDoc table, the column alignment is taken from
+    ///             --layout
+    /// org         An Org mode table, --decoration full adds rules above and
+    ///             below
+    /// sql         A CREATE TABLE statement named --table-name, with column
+    ///             types inferred from the cells, followed by INSERT
+    ///             statements
+    #[arg(
+        short = 'o',
+        long,
+        default_value_t = OutputFormat::Table,
+        verbatim_doc_comment
+    )]
+    pub output_format: OutputFormat,
+
+    /// Wrap HTML output in a complete page with a minimal stylesheet.
+    #[arg(long, default_value_t = false)]
+    pub standalone: bool,
+
+    /// Emit numbers and booleans in JSON output as such, instead of strings.
+    #[arg(long, default_value_t = false)]
+    pub infer_types: bool,
+
+    /// Use booktabs rules instead of \hline in LaTeX output.
+    #[arg(long, default_value_t = false)]
+    pub booktabs: bool,
+
+    /// Name of the table in SQL output.
+    #[arg(long, default_value = "data")]
+    pub table_name: String,
+
+    /// Number of rows inserted by each INSERT statement in SQL output.
+    #[arg(
+        long,
+        default_value_t = 100,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+    )]
+    pub batch_size: usize,
+
     /// How the table should look
     #[arg(long, default_value_t = Decoration::UnderlineHeader)]
     pub decoration: Decoration,
@@ -314,6 +605,45 @@ pub struct Args {
     #[arg(short, long, default_value_t = false)]
     pub ascii: bool,
 
+    /// The characters used for drawing table borders
+    ///
+    /// single      Box drawing lines
+    /// rounded     Box drawing lines with rounded corners
+    /// double      Double lines
+    /// heavy       Heavy lines
+    /// dashed      Dashed lines
+    /// markdown    Pipes and dashes, cells padded by spaces
+    /// psql        Like the output of psql, cells padded by spaces
+    ///
+    /// With --ascii, each theme falls back to similar ASCII characters.
+    #[arg(long, default_value_t = Theme::Single, verbatim_doc_comment)]
+    pub theme: Theme,
+
+    /// Draw table borders with custom characters instead of a --theme.
+    ///
+    /// The 12 characters are, in order: the top left corner, top tee and
+    /// top right corner, the left tee, cross and right tee of the line below
+    /// the header, the bottom left corner, bottom tee and bottom right corner,
+    /// the horizontal line at the top and bottom, the horizontal line below
+    /// the header, and the vertical line.
+    ///
+    /// Example: --border-chars '┌┬┐╞╪╡└┴┘─═│' results in:
+    /// ┌────┬────────┐
+    /// │Host│Address │
+    /// ╞════╪════════╡
+    /// │db  │10.0.0.2│
+    /// └────┴────────┘
+    #[arg(
+        long,
+        verbatim_doc_comment,
+        value_parser = clap::value_parser!(Border)
+    )]
+    pub border_chars: Option<Border>,
+
+    /// Put a space on either side of each cell of the table.
+    #[arg(long, default_value_t = false)]
+    pub padding: bool,
+
     /// Sort the output, as specified by the rules of --sort-by
     #[arg(short = 's', long)]
     pub sort: bool,
@@ -360,9 +690,112 @@ pub struct Args {
     #[arg(short = 'u', long)]
     pub unique: bool,
 
+    /// How the input should be read
+    ///
+    /// delimited   Columns are separated by --delimiter
+    /// json        An array of objects, the union of their keys is used as
+    ///             column headers
+    /// ndjson      One object per line (JSON Lines), malformed lines are
+    ///             reported and skipped
+    /// markdown    The first GitHub flavored markdown table, its alignment row
+    ///             is used as the --layout, unless specified
+    /// fixed-width Columns aligned by spaces, as printed by 'ps' or 'df'. The
+    ///             column boundaries are inferred from the header line and
+    ///             the character positions which are blank in every line
+    /// logfmt      key=value pairs, values may be quoted. The union of all
+    ///             keys is used as column headers
+    /// records     Blocks of 'Key: value' lines separated by blank lines,
+    ///             each block becomes one row. --delimiter may be used to
+    ///             separate keys and values by something other than ':'
+    #[arg(
+        short = 'i',
+        long,
+        default_value_t = InputFormat::Delimited,
+        verbatim_doc_comment
+    )]
+    pub input_format: InputFormat,
+
+    /// Ignore the first N lines of the input.
+    #[arg(long, default_value_t = 0)]
+    pub skip_lines: usize,
+
+    /// Ignore lines which are empty or only contain whitespace.
+    #[arg(long, default_value_t = false)]
+    pub skip_empty: bool,
+
+    /// Ignore lines starting with this string, leading whitespace is ignored.
+    ///
+    /// Example: --comment '#'
+    #[arg(long)]
+    pub comment: Option<String>,
+
+    /// Ignore lines matching this regular expression.
+    #[arg(long)]
+    pub comment_regex: Option<Regex>,
+
+    /// Use the Nth (zero-indexed) row as the column headers.
+    ///
+    /// Rows before it are discarded. Lines skipped by --skip-lines, --skip-empty
+    /// and the comment options are not counted. This does not apply to input
+    /// formats which provide their own headers, such as json.
+    #[arg(long)]
+    pub header_line: Option<usize>,
+
+    /// Character encoding of the input.
+    ///
+    /// utf-8         Invalid input is an error
+    /// utf-8-lossy   Invalid sequences are replaced by U+FFFD
+    /// latin1        ISO-8859-1
+    /// utf-16        Byte order is detected from the byte order mark,
+    ///               little endian if there is none
+    /// utf-16le      Little endian
+    /// utf-16be      Big endian
+    ///
+    /// A byte order mark at the start of the input is removed, and Windows
+    /// line endings (CRLF) are converted to LF.
+    #[arg(long, default_value_t = Encoding::Utf8, verbatim_doc_comment)]
+    pub encoding: Encoding,
+
+    /// How arrays in JSON input should be turned into columns.
+    ///
+    /// Nested objects are always flattened into one column per key, named by
+    /// the path of keys joined by '.', e.g. 'user.geo.lat'.
+    ///
+    /// join    Join the elements of an array with ',' into a single column
+    /// index   Create a column for every element, e.g. 'tags.0', 'tags.1'
+    #[arg(long, default_value_t = JsonArrays::Join, verbatim_doc_comment)]
+    pub json_arrays: JsonArrays,
+
     /// String by which to separate the input columns
-    #[arg(short = 'd', long, default_value = ",")]
-    pub delimiter: String,
+    ///
+    /// Fields enclosed in double quotes may contain the delimiter and line
+    /// breaks, a doubled quote ("") inside of them stands for a single one.
+    ///
+    /// If not specified, the delimiter is guessed from the first lines of the
+    /// input. Candidates are ',', tab, ';', '|' and runs of whitespace.
+    #[arg(short = 'd', long)]
+    pub delimiter: Option<String>,
+
+    /// Interpret --delimiter as a regular expression.
+    ///
+    /// Example: -r -d '\s*[,;]\s*'
+    #[arg(short = 'r', long, default_value_t = false, requires = "delimiter")]
+    pub regex_delimiter: bool,
+
+    /// Split each line into at most this many columns.
+    ///
+    /// The last column contains the remainder of the line, including any
+    /// delimiters. This is useful for output such as 'ps aux', where the last
+    /// column may contain the delimiter.
+    #[arg(
+        long,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+    )]
+    pub max_columns: Option<usize>,
+
+    /// Treat double quotes in the input as ordinary characters.
+    #[arg(long, default_value_t = false)]
+    pub no_quotes: bool,
 
     /// Collapse a sequence of multiple delimiters in a row into a single one.
     ///
@@ -370,9 +803,39 @@ pub struct Args {
     #[arg(short = 'D', long, default_value_t = false)]
     pub collapse_delimiters: bool,
 
-    /// The input file, default is STDIN
+    /// Parse each line with a regular expression instead of splitting it.
+    ///
+    /// The capture groups of the pattern become the columns. If any of the
+    /// groups are named, the group names are used as column headers, unnamed
+    /// groups are named by their index.
+    ///
+    /// Example: --pattern '^(?P<ip>\S+) \S+ \S+ \[(?P<time>[^]]+)\] "(?P<request>[^"]*)"'
+    #[arg(long, verbatim_doc_comment)]
+    pub pattern: Option<Regex>,
+
+    /// What to do with lines which do not match --pattern
+    #[arg(long, default_value_t = Unmatched::Drop)]
+    pub unmatched: Unmatched,
+
+    /// Print diagnostic information, such as the detected delimiter, to STDERR
+    #[arg(short = 'v', long, default_value_t = false)]
+    pub verbose: bool,
+
+    /// Add a column with the name of the input file and line number each row
+    /// was read from.
+    ///
+    /// It is named 'source' and follows the last column, or the columns named
+    /// by --headers. With --columns, it is the last input column.
+    #[arg(long, default_value_t = false)]
+    pub source_column: bool,
+
+    /// The input files, default is STDIN
+    ///
+    /// Multiple files are concatenated into a single table. Unless --headers
+    /// are given, the first row (or the headers provided by the input format)
+    /// of each file is used to match up the columns of the files by name.
     #[arg()]
-    pub file: Option<String>,
+    pub file: Vec<String>,
 }
 
 mod test {
@@ -415,6 +878,55 @@ mod test {
             ColumnMapping::InclusiveRange(-3, 2, "dd".to_string())
         );
     }
+    #[test]
+    fn test_theme_border() {
+        use clap::ValueEnum;
+        for theme in Theme::value_variants() {
+            let b = theme.border(true);
+            let chars = [
+                b.top_left,
+                b.top_tee,
+                b.top_right,
+                b.header_left,
+                b.header_cross,
+                b.header_right,
+                b.bottom_left,
+                b.bottom_tee,
+                b.bottom_right,
+                b.horizontal,
+                b.header,
+                b.vertical,
+            ];
+            assert!(chars.iter().all(char::is_ascii), "{}", theme);
+        }
+        assert_eq!(Theme::Rounded.border(false).top_left, '\u{256d}');
+        assert_eq!(Theme::Double.border(false).vertical, '\u{2551}');
+        assert!(Theme::Psql.border(false).padding);
+    }
+
+    #[test]
+    fn test_regex_delimiter_requires_delimiter() {
+        assert!(Args::try_parse_from(["tbl", "-r"]).is_err());
+        assert!(Args::try_parse_from(["tbl", "-r", "-d", r"\s+"]).is_ok());
+    }
+
+    #[test]
+    fn test_parser_border() {
+        assert_eq!(
+            Border::from_str("┌┬┐├┼┤└┴┘──│"),
+            Ok(Theme::Single.border(false))
+        );
+        let b = Border::from_str("abcdefghijkl").unwrap();
+        assert_eq!(
+            (b.top_left, b.header_cross, b.bottom_right),
+            ('a', 'e', 'i')
+        );
+        assert_eq!((b.horizontal, b.header, b.vertical), ('j', 'k', 'l'));
+        assert!(!b.padding);
+        assert!(Border::from_str("+-|").is_err());
+        assert!(Border::from_str("┌┬┐├┼┤└┴┘──││").is_err());
+    }
+
     #[test]
     fn test_parser_column_layout() {
         assert!(ColumnLayout::from_str("").is_err());
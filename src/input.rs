@@ -0,0 +1,1259 @@
+use core::fmt;
+extern crate itertools;
+use itertools::Itertools;
+extern crate regex;
+use regex::Regex;
+extern crate serde_json;
+use serde_json::Value;
+use std::cell::Cell;
+use std::mem;
+
+use crate::arguments::{Alignment, Encoding, JsonArrays};
+
+/// Rows read from the input, and the column headers and alignments, if the
+/// input provides them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub header: Option<Vec<String>>,
+    pub rows: Vec<Vec<String>>,
+    pub alignment: Option<Vec<Alignment>>,
+    /// For every row, the index of the input line it starts on, if known.
+    pub lines: Vec<Option<usize>>,
+}
+
+impl Table {
+    /// Combine tables, matching up their columns by header.
+    ///
+    /// The header of the result is the union of all headers, in the order they
+    /// were first seen. If a header occurs multiple times in a table, its n-th
+    /// occurrence is matched with the n-th occurrence in other tables. Tables
+    /// without a header must be given one beforehand.
+    pub fn union(tables: Vec<Table>) -> Table {
+        let keyed = |h: &Vec<String>| -> Vec<(String, usize)> {
+            h.iter()
+                .enumerate()
+                .map(|(i, c)| (c.clone(), h[..i].iter().filter(|p| *p == c).count()))
+                .collect()
+        };
+        let mut header: Vec<(String, usize)> = vec![];
+        for table in &tables {
+            for key in keyed(table.header.as_ref().unwrap_or(&vec![])) {
+                if !header.contains(&key) {
+                    header.push(key);
+                }
+            }
+        }
+        let alignment = tables.first().and_then(|t| t.alignment.clone());
+        let mut rows = vec![];
+        let mut lines = vec![];
+        for table in tables {
+            let positions: Vec<usize> = keyed(table.header.as_ref().unwrap_or(&vec![]))
+                .iter()
+                .map(|k| header.iter().position(|h| h == k).unwrap())
+                .collect();
+            for row in table.rows {
+                let mut r = vec!["".to_string(); header.len()];
+                for (cell, i) in row.into_iter().zip(&positions) {
+                    r[*i] = cell;
+                }
+                rows.push(r);
+            }
+            lines.extend(table.lines);
+        }
+        Table {
+            header: Some(header.into_iter().map(|(h, _)| h).collect()),
+            rows,
+            alignment,
+            lines,
+        }
+    }
+}
+
+/// Decode the raw input into text.
+///
+/// A byte order mark is removed, for UTF-16 it also determines the byte order.
+/// Line endings are normalized to '\n'.
+pub fn decode(bytes: &[u8], encoding: &Encoding) -> Result<String, String> {
+    let utf16 = |bytes: &[u8], from: fn([u8; 2]) -> u16| {
+        if !bytes.len().is_multiple_of(2) {
+            return Err("Input is not valid UTF-16: odd number of bytes".to_string());
+        }
+        let units: Vec<u16> = bytes.chunks(2).map(|c| from([c[0], c[1]])).collect();
+        String::from_utf16(&units).map_err(|e| format!("Input is not valid UTF-16: {}", e))
+    };
+    let text = match encoding {
+        Encoding::Utf8 => {
+            let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+            String::from_utf8(bytes.to_vec()).map_err(|e| {
+                format!(
+                    "Input is not valid UTF-8: {}, specify the --encoding",
+                    e.utf8_error()
+                )
+            })?
+        }
+        Encoding::Utf8Lossy => {
+            let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+            String::from_utf8_lossy(bytes).to_string()
+        }
+        Encoding::Latin1 => bytes.iter().map(|b| *b as char).collect(),
+        Encoding::Utf16 => match bytes {
+            [0xfe, 0xff, rest @ ..] => utf16(rest, u16::from_be_bytes)?,
+            [0xff, 0xfe, rest @ ..] => utf16(rest, u16::from_le_bytes)?,
+            _ => utf16(bytes, u16::from_le_bytes)?,
+        },
+        Encoding::Utf16le => utf16(
+            bytes.strip_prefix(b"\xff\xfe").unwrap_or(bytes),
+            u16::from_le_bytes,
+        )?,
+        Encoding::Utf16be => utf16(
+            bytes.strip_prefix(b"\xfe\xff").unwrap_or(bytes),
+            u16::from_be_bytes,
+        )?,
+    };
+    Ok(text.replace("\r\n", "\n"))
+}
+
+/// Separator between the columns of delimited input.
+#[derive(Debug, Clone)]
+pub enum Delimiter {
+    Literal(String),
+    /// Any run of whitespace; leading and trailing whitespace is ignored.
+    Whitespace,
+    /// The pattern as given by the user, and the same pattern anchored to the
+    /// start of the haystack.
+    Regex(String, Regex),
+}
+
+impl PartialEq for Delimiter {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Delimiter::Literal(a), Delimiter::Literal(b)) => a == b,
+            (Delimiter::Whitespace, Delimiter::Whitespace) => true,
+            (Delimiter::Regex(a, _), Delimiter::Regex(b, _)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Delimiter {
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern)?;
+        let re = Regex::new(&format!(r"\A(?:{})", pattern))?;
+        Ok(Delimiter::Regex(pattern.to_string(), re))
+    }
+
+    /// Make a sequence of multiple delimiters in a row count as a single one.
+    pub fn collapsed(self) -> Self {
+        let pattern = match self {
+            Delimiter::Literal(ref d) if !d.is_empty() => regex::escape(d),
+            Delimiter::Regex(ref p, _) => p.clone(),
+            _ => return self,
+        };
+        Delimiter::regex(&format!("(?:{})+", pattern)).expect("pattern was already valid")
+    }
+
+    /// Length in bytes of the delimiter starting at `pos` in `s`, if any.
+    fn match_at(&self, s: &str, pos: usize) -> Option<usize> {
+        match self {
+            Delimiter::Literal(d) if !d.is_empty() && s[pos..].starts_with(d.as_str()) => {
+                Some(d.len())
+            }
+            Delimiter::Literal(_) => None,
+            Delimiter::Regex(_, re) => re.find(&s[pos..]).map(|m| m.end()).filter(|len| *len > 0),
+            Delimiter::Whitespace => {
+                let rest = &s[pos..];
+                let len = rest.len() - rest.trim_start().len();
+                if len > 0 {
+                    Some(len)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Delimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Delimiter::Literal(d) => match d.as_str() {
+                "," => write!(f, "comma"),
+                "\t" => write!(f, "tab"),
+                ";" => write!(f, "semicolon"),
+                "|" => write!(f, "pipe"),
+                _ => write!(f, "'{}'", d),
+            },
+            Delimiter::Whitespace => write!(f, "whitespace"),
+            Delimiter::Regex(p, _) => write!(f, "/{}/", p),
+        }
+    }
+}
+
+/// Split a single record into fields.
+///
+/// If `quoting` is set, the quoting rules of RFC 4180 apply: a field that
+/// starts with '"' is quoted, delimiters and line breaks inside of it are part
+/// of the field, and '""' stands for a literal '"'. If a quoted field is not
+/// closed by the end of `first`, further lines are pulled from `rest`. Quotes
+/// inside of unquoted fields are kept as they are.
+///
+/// Once `max_columns` - 1 fields have been split off, the remainder of the line
+/// becomes the last field, verbatim.
+fn split_record<I>(
+    first: String,
+    rest: &mut I,
+    delimiter: &Delimiter,
+    quoting: bool,
+    max_columns: Option<usize>,
+) -> Vec<String>
+where
+    I: Iterator<Item = String>,
+{
+    if max_columns == Some(1) {
+        return vec![first];
+    }
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut line = first;
+    let mut pos = 0;
+    let mut in_quotes = false;
+    let mut at_field_start = true;
+    loop {
+        if in_quotes {
+            match line[pos..].find('"') {
+                Some(i) => {
+                    field.push_str(&line[pos..pos + i]);
+                    pos += i + 1;
+                    if line[pos..].starts_with('"') {
+                        field.push('"');
+                        pos += 1;
+                    } else {
+                        in_quotes = false;
+                    }
+                }
+                None => {
+                    field.push_str(&line[pos..]);
+                    match rest.next() {
+                        Some(next) => {
+                            field.push('\n');
+                            line = next;
+                            pos = 0;
+                        }
+                        None => break,
+                    }
+                }
+            }
+        } else if quoting && at_field_start && line[pos..].starts_with('"') {
+            in_quotes = true;
+            at_field_start = false;
+            pos += 1;
+        } else if let Some(len) = delimiter.match_at(&line, pos) {
+            fields.push(mem::take(&mut field));
+            at_field_start = true;
+            pos += len;
+            if max_columns.is_some_and(|m| fields.len() + 1 >= m) {
+                field.push_str(&line[pos..]);
+                break;
+            }
+        } else if let Some(c) = line[pos..].chars().next() {
+            field.push(c);
+            at_field_start = false;
+            pos += c.len_utf8();
+        } else {
+            break;
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Split every line into columns separated by `delimiter`.
+///
+/// If `quoting` is set, fields may be quoted as described in RFC 4180, in which
+/// case a single record may span multiple lines. Lines are split into at most
+/// `max_columns` columns.
+pub fn parse_delimited<I>(
+    lines: I,
+    delimiter: &Delimiter,
+    quoting: bool,
+    max_columns: Option<usize>,
+) -> Table
+where
+    I: Iterator<Item = String>,
+{
+    let consumed = Cell::new(0);
+    let mut lines = lines
+        .map(|l| match delimiter {
+            Delimiter::Whitespace => l.trim().to_string(),
+            _ => l,
+        })
+        .inspect(|_| consumed.set(consumed.get() + 1));
+    let mut rows = vec![];
+    let mut line_numbers = vec![];
+    while let Some(line) = lines.next() {
+        line_numbers.push(Some(consumed.get() - 1));
+        rows.push(split_record(
+            line,
+            &mut lines,
+            delimiter,
+            quoting,
+            max_columns,
+        ));
+    }
+    Table {
+        header: None,
+        rows,
+        alignment: None,
+        lines: line_numbers,
+    }
+}
+
+const SNIFF_LINES: usize = 20;
+
+/// Guess the delimiter of `lines` from the first few non-empty ones.
+///
+/// Each candidate is scored by the share of sampled records that have the
+/// most common number of columns. Candidates which do not split the records at
+/// all are disregarded, ties are won by the candidate listed first.
+pub fn sniff_delimiter<'a, I>(lines: I, quoting: bool) -> Delimiter
+where
+    I: Iterator<Item = &'a String>,
+{
+    let sample: Vec<String> = lines
+        .filter(|l| !l.trim().is_empty())
+        .take(SNIFF_LINES)
+        .cloned()
+        .collect();
+    let candidates = [
+        Delimiter::Literal(",".to_string()),
+        Delimiter::Literal("\t".to_string()),
+        Delimiter::Literal(";".to_string()),
+        Delimiter::Literal("|".to_string()),
+        Delimiter::Whitespace,
+    ];
+    let mut best = (0.0, candidates[0].clone());
+    for candidate in candidates {
+        let counts: Vec<usize> = parse_delimited(sample.iter().cloned(), &candidate, quoting, None)
+            .rows
+            .iter()
+            .map(|r| r.len())
+            .collect();
+        let mode = counts
+            .iter()
+            .max_by_key(|n| counts.iter().filter(|m| m == n).count())
+            .copied()
+            .unwrap_or(0);
+        if mode <= 1 {
+            continue;
+        }
+        let score = counts.iter().filter(|n| **n == mode).count() as f64 / counts.len() as f64;
+        if score > best.0 {
+            best = (score, candidate);
+        }
+    }
+    best.1
+}
+
+/// Match every line against `pattern`, its capture groups become the columns.
+///
+/// If the pattern contains named groups, their names are used as the header,
+/// unnamed groups are named by their index. A pattern without any groups
+/// yields the whole match as a single column. Lines which do not match are
+/// dropped, and returned alongside the table with their index.
+pub fn parse_pattern<I>(lines: I, pattern: &Regex) -> (Table, Vec<(usize, String)>)
+where
+    I: Iterator<Item = String>,
+{
+    let groups: Vec<usize> = if pattern.captures_len() > 1 {
+        (1..pattern.captures_len()).collect()
+    } else {
+        vec![0]
+    };
+    let names: Vec<Option<&str>> = pattern.capture_names().collect();
+    let header = if names.iter().any(|n| n.is_some()) {
+        Some(
+            groups
+                .iter()
+                .map(|i| names[*i].map_or(i.to_string(), |n| n.to_string()))
+                .collect(),
+        )
+    } else {
+        None
+    };
+    let mut rows = vec![];
+    let mut line_numbers = vec![];
+    let mut unmatched = vec![];
+    for (i, line) in lines.enumerate() {
+        match pattern.captures(&line) {
+            Some(caps) => {
+                rows.push(
+                    groups
+                        .iter()
+                        .map(|g| caps.get(*g).map_or("", |m| m.as_str()).to_string())
+                        .collect(),
+                );
+                line_numbers.push(Some(i));
+            }
+            None => unmatched.push((i, line)),
+        }
+    }
+    let table = Table {
+        header,
+        rows,
+        alignment: None,
+        lines: line_numbers,
+    };
+    (table, unmatched)
+}
+
+/// Build a table from records of key-value pairs, and the lines they start on.
+///
+/// The header is the union of all keys, in the order they were first seen.
+/// Keys missing from a record result in empty cells.
+pub fn records_to_table(records: Vec<Vec<(String, String)>>, lines: Vec<Option<usize>>) -> Table {
+    let mut header: Vec<String> = vec![];
+    for record in &records {
+        for (key, _) in record {
+            if !header.contains(key) {
+                header.push(key.clone());
+            }
+        }
+    }
+    let rows = records
+        .into_iter()
+        .map(|record| {
+            let mut row = vec!["".to_string(); header.len()];
+            for (key, value) in record {
+                let i = header.iter().position(|h| *h == key).unwrap();
+                row[i] = value;
+            }
+            row
+        })
+        .collect();
+    Table {
+        header: Some(header),
+        rows,
+        alignment: None,
+        lines,
+    }
+}
+
+/// Render a JSON value as the contents of a single cell.
+///
+/// Strings are shown without quotes and null as an empty cell, everything else
+/// as compact JSON.
+fn json_cell(value: &Value) -> String {
+    match value {
+        Value::Null => "".to_string(),
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
+/// Flatten `value` into `record`, nested keys are joined by '.'.
+///
+/// Arrays are either joined into a single cell, or flattened like objects,
+/// using the element indices as keys.
+fn flatten_json(
+    key: String,
+    value: &Value,
+    arrays: &JsonArrays,
+    record: &mut Vec<(String, String)>,
+) {
+    let prefix = |k: &str| {
+        if key.is_empty() {
+            k.to_string()
+        } else {
+            format!("{}.{}", key, k)
+        }
+    };
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (k, v) in map {
+                flatten_json(prefix(k), v, arrays, record);
+            }
+        }
+        Value::Array(values) if !values.is_empty() && *arrays == JsonArrays::Index => {
+            for (i, v) in values.iter().enumerate() {
+                flatten_json(prefix(&i.to_string()), v, arrays, record);
+            }
+        }
+        Value::Array(values) => {
+            record.push((key, values.iter().map(json_cell).join(",")));
+        }
+        Value::Object(_) => record.push((key, "".to_string())),
+        v => record.push((key, json_cell(v))),
+    }
+}
+
+/// Turn a JSON object into a record, see `records_to_table`.
+fn json_record(value: Value, arrays: &JsonArrays) -> Result<Vec<(String, String)>, String> {
+    match value {
+        Value::Object(_) => {
+            let mut record = vec![];
+            flatten_json("".to_string(), &value, arrays, &mut record);
+            Ok(record)
+        }
+        v => Err(format!("Expected an object, found {}", v)),
+    }
+}
+
+/// Parse a JSON array of objects, each object becomes one row.
+///
+/// Nested values are flattened into columns as described in `flatten_json`.
+/// A single top-level object is treated like an array containing only it.
+pub fn parse_json(text: &str, arrays: &JsonArrays) -> Result<Table, String> {
+    let values = match serde_json::from_str(text).map_err(|e| e.to_string())? {
+        Value::Array(values) => values,
+        v => vec![v],
+    };
+    let records = values
+        .into_iter()
+        .map(|v| json_record(v, arrays))
+        .collect::<Result<Vec<_>, _>>()?;
+    let lines = vec![None; records.len()];
+    Ok(records_to_table(records, lines))
+}
+
+/// Parse JSON Lines, one object per line, each object becomes one row.
+///
+/// Nested values are flattened into columns as described in `flatten_json`.
+/// Empty lines are skipped. Malformed lines are skipped as well, and their
+/// index and error are returned alongside the table.
+pub fn parse_ndjson<I>(lines: I, arrays: &JsonArrays) -> (Table, Vec<(usize, String)>)
+where
+    I: Iterator<Item = String>,
+{
+    let mut records = vec![];
+    let mut line_numbers = vec![];
+    let mut errors = vec![];
+    for (i, line) in lines.enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line)
+            .map_err(|e| e.to_string())
+            .and_then(|v| json_record(v, arrays))
+        {
+            Ok(record) => {
+                records.push(record);
+                line_numbers.push(Some(i));
+            }
+            Err(e) => errors.push((i, e)),
+        }
+    }
+    (records_to_table(records, line_numbers), errors)
+}
+
+/// Split a row of a markdown table into its cells.
+///
+/// Leading and trailing pipes are optional, '\|' is a literal pipe.
+fn split_markdown_row(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(l) if !l.ends_with('\\') => l,
+        _ => line,
+    };
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => cell.push(chars.next().unwrap()),
+            '|' => cells.push(mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell);
+    cells.iter().map(|c| c.trim().to_string()).collect()
+}
+
+/// Parse the delimiter row of a markdown table, e.g. '|---|:--:|--:|'.
+fn markdown_alignment(cells: &[String]) -> Option<Vec<Alignment>> {
+    let re = Regex::new(r"^(:?)-+(:?)$").unwrap();
+    cells
+        .iter()
+        .map(|c| {
+            let cap = re.captures(c)?;
+            Some(match (&cap[1], &cap[2]) {
+                (":", ":") => Alignment::Center,
+                ("", ":") => Alignment::Right,
+                _ => Alignment::Left,
+            })
+        })
+        .collect()
+}
+
+/// Parse the first GitHub flavored markdown table found in `lines`.
+///
+/// The header row is used as the header, the delimiter row determines the
+/// alignment of the columns. The table ends at the first line that does not
+/// contain a pipe.
+pub fn parse_markdown<I>(lines: I) -> Table
+where
+    I: Iterator<Item = String>,
+{
+    let mut header: Option<Vec<String>> = None;
+    let mut alignment = None;
+    let mut rows = vec![];
+    let mut line_numbers = vec![];
+    for (i, line) in lines.enumerate() {
+        let in_table = line.contains('|');
+        if alignment.is_some() {
+            if !in_table {
+                break;
+            }
+            let n_columns = header.as_ref().map_or(0, |h| h.len());
+            let mut row = split_markdown_row(&line);
+            row.truncate(n_columns);
+            rows.push(row);
+            line_numbers.push(Some(i));
+        } else if !in_table {
+            header = None;
+        } else if let Some(ref h) = header {
+            let cells = split_markdown_row(&line);
+            match markdown_alignment(&cells) {
+                Some(a) if a.len() == h.len() => alignment = Some(a),
+                _ => header = Some(cells),
+            }
+        } else {
+            header = Some(split_markdown_row(&line));
+        }
+    }
+    if alignment.is_none() {
+        header = None;
+    }
+    Table {
+        header,
+        rows,
+        alignment,
+        lines: line_numbers,
+    }
+}
+
+/// Parse column aligned text, such as the output of 'ps', 'df' or 'docker ps'.
+///
+/// Columns start after character positions which are blank in every line. The
+/// first line is the header, columns whose header would be blank are merged
+/// into the column to their left, so cells containing spaces stay intact.
+pub fn parse_fixed_width<I>(lines: I) -> Table
+where
+    I: Iterator<Item = String>,
+{
+    let (line_numbers, lines): (Vec<usize>, Vec<Vec<char>>) = lines
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| (i, l.trim_end().chars().collect()))
+        .unzip();
+    let Some(header) = lines.first() else {
+        return Table {
+            header: None,
+            rows: vec![],
+            alignment: None,
+            lines: vec![],
+        };
+    };
+    let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+    let blank: Vec<bool> = (0..width)
+        .map(|c| {
+            lines
+                .iter()
+                .all(|l| l.get(c).is_none_or(|ch| ch.is_whitespace()))
+        })
+        .collect();
+    let is_blank = |l: &[char], from: usize, to: usize| {
+        l.iter().take(to).skip(from).all(|ch| ch.is_whitespace())
+    };
+    let mut starts: Vec<usize> = (0..width)
+        .filter(|c| !blank[*c] && (*c == 0 || blank[c - 1]))
+        .collect();
+    let mut i = 1;
+    while i < starts.len() {
+        let end = starts.get(i + 1).copied().unwrap_or(width);
+        if is_blank(header, starts[i], end) {
+            starts.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    let slice = |l: &Vec<char>| -> Vec<String> {
+        starts
+            .iter()
+            .enumerate()
+            .map(|(i, from)| {
+                let to = starts.get(i + 1).copied().unwrap_or(width);
+                l.iter()
+                    .take(to)
+                    .skip(*from)
+                    .collect::<String>()
+                    .trim()
+                    .to_string()
+            })
+            .collect()
+    };
+    Table {
+        header: Some(slice(header)),
+        rows: lines[1..].iter().map(slice).collect(),
+        alignment: None,
+        lines: line_numbers[1..].iter().map(|i| Some(*i)).collect(),
+    }
+}
+
+/// Split a logfmt line into its key-value pairs.
+///
+/// Values may be quoted with '"', within which backslash escapes are
+/// supported. A key without '=' has an empty value.
+fn split_logfmt(line: &str) -> Vec<(String, String)> {
+    let mut pairs = vec![];
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '=') {
+            key.push(c);
+        }
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            if chars.next_if_eq(&'"').is_some() {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some('r') => value.push('\r'),
+                            Some(e) => value.push(e),
+                            None => value.push('\\'),
+                        },
+                        c => value.push(c),
+                    }
+                }
+            } else {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    value.push(c);
+                }
+            }
+        }
+        if !key.is_empty() {
+            pairs.push((key, value));
+        }
+    }
+    pairs
+}
+
+/// Parse logfmt, 'key=value' pairs separated by whitespace, one record per
+/// line. The union of all keys is used as the header.
+pub fn parse_logfmt<I>(lines: I) -> Table
+where
+    I: Iterator<Item = String>,
+{
+    let (line_numbers, records) = lines
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| (Some(i), split_logfmt(&l)))
+        .unzip();
+    records_to_table(records, line_numbers)
+}
+
+/// Parse blocks of 'Key: value' lines separated by blank lines, as found in
+/// '/proc/cpuinfo' or the output of 'dpkg -s'.
+///
+/// Every block becomes one row, keys and values are separated by the first
+/// occurrence of `separator`. Indented lines continue the previous value.
+pub fn parse_records<I>(lines: I, separator: &str) -> Table
+where
+    I: Iterator<Item = String>,
+{
+    let mut records = vec![];
+    let mut line_numbers = vec![];
+    let mut record: Vec<(String, String)> = vec![];
+    for (i, line) in lines.enumerate() {
+        if line.trim().is_empty() {
+            if !record.is_empty() {
+                records.push(mem::take(&mut record));
+            }
+            continue;
+        }
+        if record.is_empty() {
+            line_numbers.push(Some(i));
+        }
+        match record.last_mut() {
+            Some((_, value)) if line.starts_with(char::is_whitespace) => {
+                value.push('\n');
+                value.push_str(line.trim());
+            }
+            _ => {
+                let (key, value) = line.split_once(separator).unwrap_or((&line, ""));
+                record.push((key.trim().to_string(), value.trim().to_string()));
+            }
+        }
+    }
+    if !record.is_empty() {
+        records.push(record);
+    }
+    records_to_table(records, line_numbers)
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    fn parse(s: &str, delimiter: &str) -> Vec<Vec<String>> {
+        parse_delimited(
+            s.lines().map(|l| l.to_string()),
+            &Delimiter::Literal(delimiter.to_string()),
+            true,
+            None,
+        )
+        .rows
+    }
+
+    #[allow(dead_code)]
+    fn table(header: &[&str], rows: &[&[&str]]) -> Table {
+        let strings = |r: &[&str]| r.iter().map(|c| c.to_string()).collect();
+        Table {
+            header: Some(strings(header)),
+            rows: rows.iter().map(|r| strings(r)).collect(),
+            alignment: None,
+            lines: (0..rows.len()).map(Some).collect(),
+        }
+    }
+
+    #[test]
+    fn test_table_union() {
+        let union = Table::union(vec![
+            table(&["id", "x", "x"], &[&["1", "a", "b"], &["2"]]),
+            table(&["x", "name", "id"], &[&["c", "bob", "3"]]),
+            table(&["x", "x", "x"], &[&["d", "e", "f"]]),
+        ]);
+        assert_eq!(union.header.unwrap(), vec!["id", "x", "x", "name", "x"]);
+        assert_eq!(
+            union.rows,
+            vec![
+                vec!["1", "a", "b", "", ""],
+                vec!["2", "", "", "", ""],
+                vec!["3", "c", "", "bob", ""],
+                vec!["", "d", "e", "", "f"],
+            ]
+        );
+        assert_eq!(union.lines, vec![Some(0), Some(1), Some(0), Some(0)]);
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            decode(b"\xef\xbb\xbfName,V\r\na,\"x\r\ny\"\r\n", &Encoding::Utf8).unwrap(),
+            "Name,V\na,\"x\ny\"\n"
+        );
+        assert!(decode(b"caf\xe9", &Encoding::Utf8).is_err());
+        assert_eq!(
+            decode(b"caf\xe9", &Encoding::Utf8Lossy).unwrap(),
+            "caf\u{fffd}"
+        );
+        assert_eq!(decode(b"caf\xe9", &Encoding::Latin1).unwrap(), "café");
+        assert_eq!(
+            decode(b"\xff\xfea\x00\xe9\x00", &Encoding::Utf16).unwrap(),
+            "aé"
+        );
+        assert_eq!(
+            decode(b"\xfe\xff\x00a\x00\xe9", &Encoding::Utf16).unwrap(),
+            "aé"
+        );
+        assert_eq!(decode(b"\x00a\x00\xe9", &Encoding::Utf16be).unwrap(), "aé");
+        assert_eq!(
+            decode(b"\xff\xfea\x00\r\x00\n\x00", &Encoding::Utf16le).unwrap(),
+            "a\n"
+        );
+        assert!(decode(b"a\x00b", &Encoding::Utf16le).is_err());
+    }
+
+    #[test]
+    fn test_parse_delimited_quoted() {
+        assert_eq!(parse("a,b,c", ","), vec![vec!["a", "b", "c"]]);
+        assert_eq!(parse("a,,", ","), vec![vec!["a", "", ""]]);
+        assert_eq!(
+            parse("\"Doe, John\",42", ","),
+            vec![vec!["Doe, John", "42"]]
+        );
+        assert_eq!(
+            parse("\"say \"\"hi\"\"\",\"\"", ","),
+            vec![vec!["say \"hi\"", ""]]
+        );
+        assert_eq!(
+            parse("1,\"multi\nline\",2\n3,x,4", ","),
+            vec![vec!["1", "multi\nline", "2"], vec!["3", "x", "4"]]
+        );
+        assert_eq!(
+            parse("5\" screen,\"a\"b", ","),
+            vec![vec!["5\" screen", "ab"]]
+        );
+        assert_eq!(parse("\"a::b\"::c", "::"), vec![vec!["a::b", "c"]]);
+        assert_eq!(parse("\"unterminated,x", ","), vec![vec!["unterminated,x"]]);
+    }
+
+    #[test]
+    fn test_parse_delimited_unquoted() {
+        let rows = parse_delimited(
+            vec!["\"a,b\",c".to_string()].into_iter(),
+            &Delimiter::Literal(",".to_string()),
+            false,
+            None,
+        )
+        .rows;
+        assert_eq!(rows, vec![vec!["\"a", "b\"", "c"]]);
+        let rows = parse_delimited(
+            vec!["  a  b\tc ".to_string()].into_iter(),
+            &Delimiter::Whitespace,
+            false,
+            None,
+        )
+        .rows;
+        assert_eq!(rows, vec![vec!["a", "b", "c"]]);
+    }
+
+    #[test]
+    fn test_parse_delimited_regex() {
+        let parse_re = |s: &str, d: Delimiter| {
+            parse_delimited(s.lines().map(|l| l.to_string()), &d, true, None).rows
+        };
+        assert_eq!(
+            parse_re("a  b\tc", Delimiter::regex(r"\s+").unwrap()),
+            vec![vec!["a", "b", "c"]]
+        );
+        assert_eq!(
+            parse_re("a , b;\"c;d\"", Delimiter::regex(r"\s*[,;]\s*").unwrap()),
+            vec![vec!["a", "b", "c;d"]]
+        );
+        assert_eq!(
+            parse_re("key: value: more", Delimiter::regex(": ").unwrap()),
+            vec![vec!["key", "value", "more"]]
+        );
+        assert_eq!(
+            parse_re("a1b22c", Delimiter::regex(r"\d*").unwrap()),
+            vec![vec!["a", "b", "c"]]
+        );
+        assert!(Delimiter::regex("(").is_err());
+    }
+
+    #[test]
+    fn test_collapsed_delimiter() {
+        let parse_c = |s: &str, d: Delimiter| {
+            parse_delimited(s.lines().map(|l| l.to_string()), &d.collapsed(), true, None).rows
+        };
+        assert_eq!(
+            parse_c("a,,b,c,,,", Delimiter::Literal(",".to_string())),
+            vec![vec!["a", "b", "c", ""]]
+        );
+        assert_eq!(
+            parse_c("a.*.*b", Delimiter::Literal(".*".to_string())),
+            vec![vec!["a", "b"]]
+        );
+        assert_eq!(
+            parse_c("a; ;b", Delimiter::regex("; ?").unwrap()),
+            vec![vec!["a", "b"]]
+        );
+        assert_eq!(
+            parse_c(" a  b ", Delimiter::Whitespace),
+            vec![vec!["a", "b"]]
+        );
+    }
+
+    #[test]
+    fn test_parse_delimited_max_columns() {
+        let parse_m = |s: &str, d: Delimiter, m: usize| {
+            parse_delimited(s.lines().map(|l| l.to_string()), &d, true, Some(m)).rows
+        };
+        assert_eq!(
+            parse_m(
+                "root  1  0.0 /usr/bin/foo --bar  baz",
+                Delimiter::Whitespace,
+                4
+            ),
+            vec![vec!["root", "1", "0.0", "/usr/bin/foo --bar  baz"]]
+        );
+        assert_eq!(
+            parse_m(
+                "a,\"b,c\",d,\"e,f\"",
+                Delimiter::Literal(",".to_string()),
+                3
+            ),
+            vec![vec!["a", "b,c", "d,\"e,f\""]]
+        );
+        assert_eq!(
+            parse_m("a,b,c", Delimiter::Literal(",".to_string()), 1),
+            vec![vec!["a,b,c"]]
+        );
+        assert_eq!(
+            parse_m("a,b", Delimiter::Literal(",".to_string()), 5),
+            vec![vec!["a", "b"]]
+        );
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        let lines = || {
+            vec![
+                "GET /index.html 200".to_string(),
+                "garbage".to_string(),
+                "POST /api 404".to_string(),
+            ]
+            .into_iter()
+        };
+        let named = Regex::new(r"^(?P<method>\w+) (\S+) (?P<status>\d+)$").unwrap();
+        assert_eq!(
+            parse_pattern(lines(), &named).0,
+            Table {
+                header: Some(vec![
+                    "method".to_string(),
+                    "2".to_string(),
+                    "status".to_string()
+                ]),
+                rows: vec![
+                    vec![
+                        "GET".to_string(),
+                        "/index.html".to_string(),
+                        "200".to_string()
+                    ],
+                    vec!["POST".to_string(), "/api".to_string(), "404".to_string()],
+                ],
+                alignment: None,
+                lines: vec![Some(0), Some(2)],
+            }
+        );
+        let unnamed = Regex::new(r"(\w+) (/)?(x)?").unwrap();
+        assert_eq!(
+            parse_pattern(lines(), &unnamed).0.rows[0],
+            vec!["GET", "/", ""]
+        );
+        assert_eq!(parse_pattern(lines(), &unnamed).0.header, None);
+        let no_groups = Regex::new(r"\d+").unwrap();
+        assert_eq!(
+            parse_pattern(lines(), &no_groups).0.rows,
+            vec![vec!["200"], vec!["404"]]
+        );
+        assert_eq!(
+            parse_pattern(lines(), &named).1,
+            vec![(1, "garbage".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let table = parse_json(
+            r#"[
+                {"id": 1, "name": "x", "tags": ["a", "b"]},
+                {"name": "y", "extra": {}, "id": 2.5},
+                {"id": null, "ok": true}
+            ]"#,
+            &JsonArrays::Join,
+        )
+        .unwrap();
+        assert_eq!(
+            table.header.unwrap(),
+            vec!["id", "name", "tags", "extra", "ok"]
+        );
+        assert_eq!(
+            table.rows,
+            vec![
+                vec!["1", "x", "a,b", "", ""],
+                vec!["2.5", "y", "", "", ""],
+                vec!["", "", "", "", "true"],
+            ]
+        );
+        assert_eq!(
+            parse_json(r#"{"a": "b"}"#, &JsonArrays::Join).unwrap().rows,
+            vec![vec!["b"]]
+        );
+        assert!(parse_json("[1, 2]", &JsonArrays::Join).is_err());
+        assert!(parse_json("[{]", &JsonArrays::Join).is_err());
+    }
+
+    #[test]
+    fn test_flatten_json() {
+        let text = r#"{
+            "user": {"id": 1, "geo": {"lat": 1.5, "lng": -2}},
+            "tags": ["a", {"b": 1}],
+            "empty": []
+        }"#;
+        let table = parse_json(text, &JsonArrays::Join).unwrap();
+        assert_eq!(
+            table.header.unwrap(),
+            vec!["user.id", "user.geo.lat", "user.geo.lng", "tags", "empty"]
+        );
+        assert_eq!(table.rows, vec![vec!["1", "1.5", "-2", r#"a,{"b":1}"#, ""]]);
+        let table = parse_json(text, &JsonArrays::Index).unwrap();
+        assert_eq!(
+            table.header.unwrap(),
+            vec![
+                "user.id",
+                "user.geo.lat",
+                "user.geo.lng",
+                "tags.0",
+                "tags.1.b",
+                "empty"
+            ]
+        );
+        assert_eq!(table.rows, vec![vec!["1", "1.5", "-2", "a", "1", ""]]);
+    }
+
+    #[test]
+    fn test_parse_ndjson() {
+        let lines = vec![
+            r#"{"level": "info", "msg": "started"}"#,
+            "",
+            r#"{"msg": "oops", "err": {"code": 5}"#,
+            r#"{"msg": "done", "dur": 12, "level": "debug"}"#,
+            "[1]",
+        ];
+        let (table, errors) =
+            parse_ndjson(lines.into_iter().map(|l| l.to_string()), &JsonArrays::Join);
+        assert_eq!(table.header.unwrap(), vec!["level", "msg", "dur"]);
+        assert_eq!(
+            errors.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        assert_eq!(
+            table.rows,
+            vec![vec!["info", "started", ""], vec!["debug", "done", "12"]]
+        );
+    }
+
+    #[test]
+    fn test_parse_markdown() {
+        let text = "# Title\n\
+            Some text | with a pipe\n\
+            \n\
+            | Name | Op | Count |\n\
+            |:-----|:--:|------:|\n\
+            | a    | `\\|` | 1 |\n\
+            b | c\n\
+            | x | y | z | extra |\n\
+            \n\
+            | other | table |\n\
+            |---|---|\n";
+        let table = parse_markdown(text.lines().map(|l| l.to_string()));
+        assert_eq!(table.header.unwrap(), vec!["Name", "Op", "Count"]);
+        assert_eq!(
+            table.alignment.unwrap(),
+            vec![Alignment::Left, Alignment::Center, Alignment::Right]
+        );
+        assert_eq!(
+            table.rows,
+            vec![vec!["a", "`|`", "1"], vec!["b", "c"], vec!["x", "y", "z"]]
+        );
+        let table = parse_markdown("a|b\nc|d".lines().map(|l| l.to_string()));
+        assert_eq!(table.header, None);
+        assert_eq!(table.rows.len(), 0);
+    }
+
+    #[test]
+    fn test_parse_fixed_width() {
+        let text = "\
+CONTAINER ID   IMAGE          STATUS          NAMES
+4f1a2b3c4d5e   nginx:1.25     Up 2 hours      web
+9e8d7c6b5a4f   postgres:16    Exited (0) 3s   db
+";
+        let table = parse_fixed_width(text.lines().map(|l| l.to_string()));
+        assert_eq!(
+            table.header.unwrap(),
+            vec!["CONTAINER ID", "IMAGE", "STATUS", "NAMES"]
+        );
+        assert_eq!(
+            table.rows,
+            vec![
+                vec!["4f1a2b3c4d5e", "nginx:1.25", "Up 2 hours", "web"],
+                vec!["9e8d7c6b5a4f", "postgres:16", "Exited (0) 3s", "db"]
+            ]
+        );
+        let text = "\
+USER   PID COMMAND
+root     1 init
+alice 4242 vim  a b
+";
+        let table = parse_fixed_width(text.lines().map(|l| l.to_string()));
+        assert_eq!(table.header.unwrap(), vec!["USER", "PID", "COMMAND"]);
+        assert_eq!(
+            table.rows,
+            vec![vec!["root", "1", "init"], vec!["alice", "4242", "vim  a b"]]
+        );
+        let table = parse_fixed_width(vec![].into_iter());
+        assert_eq!(table.header, None);
+    }
+
+    #[test]
+    fn test_parse_logfmt() {
+        let text = r#"level=info msg="request done" dur=12ms
+level=error msg="say \"hi\"\n" err= retry
+
+ts=1 level=debug  msg=plain"#;
+        let table = parse_logfmt(text.lines().map(|l| l.to_string()));
+        assert_eq!(
+            table.header.unwrap(),
+            vec!["level", "msg", "dur", "err", "retry", "ts"]
+        );
+        assert_eq!(
+            table.rows,
+            vec![
+                vec!["info", "request done", "12ms", "", "", ""],
+                vec!["error", "say \"hi\"\n", "", "", "", ""],
+                vec!["debug", "plain", "", "", "", "1"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_records() {
+        let text = "\
+processor\t: 0
+model name\t: Some CPU @ 3.00GHz
+
+
+processor\t: 1
+flags\t\t: fpu vme
+Description: first line
+ second line
+ third line
+";
+        let table = parse_records(text.lines().map(|l| l.to_string()), ":");
+        assert_eq!(
+            table.header.unwrap(),
+            vec!["processor", "model name", "flags", "Description"]
+        );
+        assert_eq!(
+            table.rows,
+            vec![
+                vec!["0", "Some CPU @ 3.00GHz", "", ""],
+                vec!["1", "", "fpu vme", "first line\nsecond line\nthird line"],
+            ]
+        );
+        let table = parse_records("a=1=2\nb".lines().map(|l| l.to_string()), "=");
+        assert_eq!(table.header.unwrap(), vec!["a", "b"]);
+        assert_eq!(table.rows, vec![vec!["1=2", ""]]);
+    }
+
+    #[allow(dead_code)]
+    fn sniff(s: &str) -> Delimiter {
+        let lines: Vec<String> = s.lines().map(|l| l.to_string()).collect();
+        sniff_delimiter(lines.iter(), true)
+    }
+
+    #[test]
+    fn test_sniff_delimiter() {
+        let lit = |d: &str| Delimiter::Literal(d.to_string());
+        assert_eq!(sniff("a,b,c\n1,2,3"), lit(","));
+        assert_eq!(sniff("a b\tc\n1 2\t3"), lit("\t"));
+        assert_eq!(sniff("Name;Price\n\"Foo, Inc\";3,50"), lit(";"));
+        assert_eq!(sniff("a|b\nc|d"), lit("|"));
+        assert_eq!(
+            sniff("PID  TTY  CMD\n1 ?  init\n22 pts/0 bash"),
+            Delimiter::Whitespace
+        );
+        assert_eq!(sniff("a b,c\nd e,f"), lit(","));
+        assert_eq!(sniff("single\ncolumn"), lit(","));
+    }
+}
@@ -1,17 +1,31 @@
 use clap::Parser;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader};
+use std::process::exit;
 
 pub mod arguments;
+pub mod input;
 pub mod output;
 pub mod process;
 
 fn main() {
     let args = arguments::Args::parse();
-    let input: Box<dyn BufRead> = match args.file {
-        Some(ref f) => Box::new(BufReader::new(File::open(f).expect("Failed to open file"))),
-        None => Box::new(BufReader::new(io::stdin())),
+    let inputs: Vec<(String, Box<dyn BufRead>)> = if args.file.is_empty() {
+        vec![("stdin".to_string(), Box::new(BufReader::new(io::stdin())))]
+    } else {
+        args.file
+            .iter()
+            .map(|f| -> (String, Box<dyn BufRead>) {
+                match File::open(f) {
+                    Ok(file) => (f.clone(), Box::new(BufReader::new(file))),
+                    Err(e) => {
+                        eprintln!("Failed to open {}: {}", f, e);
+                        exit(1);
+                    }
+                }
+            })
+            .collect()
     };
-    let columns = process::process(&args, input);
-    output::display(args, &columns);
+    let (columns, layout) = process::process(&args, inputs);
+    output::display(args, &columns, layout);
 }
@@ -1,4 +1,5 @@
 use itertools::Itertools;
+use std::collections::HashSet;
 
 use crate::arguments::*;
 
@@ -20,61 +21,66 @@ fn align_and_trim(s: &String, align: &Alignment, w: usize, spec: &WidthSpecifier
     }
 }
 
-pub fn format_row(args: &Args, column_widths: &Vec<usize>, row: &Vec<String>) -> String {
-    if let Some(ref layout) = args.layout {
-        layout
+fn border(args: &Args) -> Border {
+    let mut border = match args.border_chars {
+        Some(ref b) => b.clone(),
+        None => args.theme.border(args.ascii),
+    };
+    border.padding |= args.padding;
+    border
+}
+
+/// Join the cells of a line, interleaved with the delimiters of the layout.
+///
+/// Each '|' of a delimiter is drawn as `vertical`, every other character as
+/// `space`. Without a layout, the cells are separated by `vertical`.
+fn join_cells(args: &Args, cells: Vec<String>, vertical: char, space: char) -> String {
+    match args.layout {
+        Some(ref layout) => layout
             .delimiters
             .iter()
             .map(|d| {
-                if args.ascii {
-                    d.clone()
-                } else {
-                    d.chars()
-                        .map(|c| if c == '|' { '\u{2502}' } else { c })
-                        .collect()
-                }
-            })
-            .interleave(
-                row.iter()
-                    .zip(column_widths)
-                    .enumerate()
-                    .map(|(i, (h, c))| {
-                        let fw = args.fixed_width.clone().unwrap_or(vec![]);
-                        let fw = fw.get(i).unwrap_or(&WidthSpecifier::Indeterminate);
-                        align_and_trim(
-                            h,
-                            layout.column_align.get(i).unwrap_or(&Alignment::Left),
-                            *c,
-                            fw,
-                        )
-                    }),
-            )
-            .collect()
-    } else {
-        row.iter()
-            .enumerate()
-            .map(|(i, h)| {
-                align_and_trim(
-                    h,
-                    &Alignment::Left,
-                    column_widths[i],
-                    &WidthSpecifier::Indeterminate,
-                )
+                d.chars()
+                    .map(|c| if c == '|' { vertical } else { space })
+                    .collect::<String>()
             })
-            .join(if args.ascii { &"|" } else { &"\u{2502}" })
+            .interleave(cells)
+            .collect(),
+        None => cells.join(&vertical.to_string()),
     }
 }
 
-fn replace_with_if<FA, FB>(cond: &bool, s: &String, fa: FA, fb: FB) -> String
-where
-    FA: FnMut(char) -> char,
-    FB: FnMut(char) -> char,
-{
-    if *cond {
-        s.chars().map(fa).collect()
-    } else {
-        s.chars().map(fb).collect()
-    }
+pub fn format_row(args: &Args, column_widths: &[usize], row: &[String]) -> String {
+    let border = border(args);
+    let cells = align_row(args, column_widths, row)
+        .into_iter()
+        .map(|c| {
+            if border.padding {
+                format!(" {} ", c)
+            } else {
+                c
+            }
+        })
+        .collect();
+    join_cells(args, cells, border.vertical, ' ')
+}
+
+/// Draw a horizontal line spanning the first `columns` columns, with `junction`
+/// wherever a row has a vertical border.
+fn format_rule(
+    args: &Args,
+    column_widths: &[usize],
+    columns: usize,
+    junction: char,
+    fill: char,
+) -> String {
+    let padding = if border(args).padding { 2 } else { 0 };
+    let cells = column_widths
+        .iter()
+        .take(columns)
+        .map(|w| fill.to_string().repeat(w + padding))
+        .collect();
+    join_cells(args, cells, junction, fill)
 }
 
 fn calculate_column_widths(args: &Args, header: &Vec<String>, data: &[Vec<String>]) -> Vec<usize> {
@@ -106,86 +112,927 @@ fn calculate_column_widths(args: &Args, header: &Vec<String>, data: &[Vec<String
     column_widths
 }
 
-pub fn display(args: Args, rows: &Vec<Vec<String>>) {
-    if rows.len() == 0 {
-        return;
+/// Split a row whose cells contain line breaks into multiple physical rows.
+fn split_multiline(row: &[String]) -> Vec<Vec<String>> {
+    let cells: Vec<Vec<&str>> = row.iter().map(|c| c.lines().collect()).collect();
+    let height = cells.iter().map(|c| c.len()).max().unwrap_or(0).max(1);
+    (0..height)
+        .map(|i| {
+            cells
+                .iter()
+                .map(|c| c.get(i).unwrap_or(&"").to_string())
+                .collect()
+        })
+        .collect()
+}
+
+fn column_alignment(args: &Args, i: usize) -> &Alignment {
+    args.layout
+        .as_ref()
+        .and_then(|l| l.column_align.get(i))
+        .unwrap_or(&Alignment::Left)
+}
+
+/// Apply the fixed width and alignment of each column to the cells of `row`.
+fn align_row(args: &Args, column_widths: &[usize], row: &[String]) -> Vec<String> {
+    row.iter()
+        .zip(column_widths)
+        .enumerate()
+        .map(|(i, (c, w))| {
+            let fw = args.fixed_width.clone().unwrap_or(vec![]);
+            let fw = fw.get(i).unwrap_or(&WidthSpecifier::Indeterminate);
+            align_and_trim(c, column_alignment(args, i), *w, fw)
+        })
+        .collect()
+}
+
+/// Render a GitHub flavored markdown table.
+fn markdown(args: &Args, header: &[String], data: &[Vec<String>]) -> String {
+    let escape = |row: &[String]| -> Vec<String> {
+        row.iter()
+            .map(|c| c.replace('|', "\\|").replace('\n', "<br>"))
+            .collect()
+    };
+    let header = escape(header);
+    let data: Vec<Vec<String>> = data.iter().map(|r| escape(r)).collect();
+    let column_widths: Vec<usize> = calculate_column_widths(args, &header, &data)
+        .iter()
+        .map(|w| usize::max(*w, 3))
+        .collect();
+    let format = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+    let separator = column_widths
+        .iter()
+        .take(header.len())
+        .enumerate()
+        .map(|(i, w)| match (&args.layout, column_alignment(args, i)) {
+            (None, _) => "-".repeat(*w),
+            (_, Alignment::Left) => format!(":{}", "-".repeat(w - 1)),
+            (_, Alignment::Center) => format!(":{}:", "-".repeat(w - 2)),
+            (_, Alignment::Right) => format!("{}:", "-".repeat(w - 1)),
+        })
+        .collect();
+    let mut out = format(align_row(args, &column_widths, &header));
+    out += &format(separator);
+    for row in &data {
+        out += &format(align_row(args, &column_widths, row));
     }
-    let (header, data) = match args.headers {
-        Some(ref h) => (
-            h.iter()
-                .take(rows.get(1).unwrap_or(h).len())
-                .map(|x| x.clone())
-                .collect(),
-            &rows[..],
-        ),
-        None => (rows[0].clone(), &rows[1..]),
-    };
-
-    let column_widths = calculate_column_widths(&args, &header, data);
-    let header_text = format_row(&args, &column_widths, &header);
-    let header_underline = replace_with_if(
-        &args.ascii,
-        &header_text,
-        |c| {
-            // FIXME: if a '|' is contained in the title text, it results in + being
-            // printed at the wrong place. Users shouldn't do that though
-            match c {
-                '|' => '+',
-                _ => '-',
+    out
+}
+
+fn escape_html(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            '\'' => "&#39;".to_string(),
+            '\n' => "<br>".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+const HTML_STYLE: &str = "\
+table { border-collapse: collapse; font-family: sans-serif; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.75em; }
+thead th { background: #eee; }
+tbody tr:nth-child(even) { background: #f8f8f8; }
+";
+
+/// Render an HTML table, optionally as a standalone page.
+fn html(args: &Args, header: &[String], data: &[Vec<String>]) -> String {
+    let format = |tag: &str, row: &[String]| -> String {
+        let cells: String = row
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let style = match (&args.layout, column_alignment(args, i)) {
+                    (None, _) => "",
+                    (_, Alignment::Left) => " style=\"text-align: left\"",
+                    (_, Alignment::Center) => " style=\"text-align: center\"",
+                    (_, Alignment::Right) => " style=\"text-align: right\"",
+                };
+                format!("<{tag}{style}>{}</{tag}>", escape_html(c))
+            })
+            .collect();
+        format!("<tr>{}</tr>\n", cells)
+    };
+    let mut out = String::from("<table>\n<thead>\n");
+    out += &format("th", header);
+    out += "</thead>\n<tbody>\n";
+    for row in data {
+        out += &format("td", row);
+    }
+    out += "</tbody>\n</table>\n";
+    if args.standalone {
+        out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<style>\n{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            HTML_STYLE, out
+        );
+    }
+    out
+}
+
+/// Render delimiter separated values, quoting cells as described in RFC 4180.
+fn delimited(header: &[String], data: &[Vec<String>], delimiter: char) -> String {
+    let quote = |c: &String| -> String {
+        if c.contains([delimiter, '"', '\n', '\r']) {
+            format!("\"{}\"", c.replace('"', "\"\""))
+        } else {
+            c.clone()
+        }
+    };
+    std::iter::once(header)
+        .chain(data.iter().map(|r| &r[..]))
+        .map(|row| row.iter().map(quote).join(&delimiter.to_string()) + "\n")
+        .collect()
+}
+
+fn json_value(args: &Args, cell: &str) -> serde_json::Value {
+    if args.infer_types {
+        if let Ok(n) = serde_json::from_str::<serde_json::Number>(cell) {
+            return serde_json::Value::Number(n);
+        }
+        match cell {
+            "true" => return serde_json::Value::Bool(true),
+            "false" => return serde_json::Value::Bool(false),
+            _ => {}
+        }
+    }
+    serde_json::Value::String(cell.to_string())
+}
+
+/// Make repeated names unique, by appending `_1`, `_2`, ... to them.
+fn unique_names(names: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    names
+        .into_iter()
+        .map(|name| {
+            let mut unique = name.clone();
+            let mut n = 0;
+            while !seen.insert(unique.clone()) {
+                n += 1;
+                unique = format!("{}_{}", name, n);
             }
-        },
-        |c| match c {
-            '\u{2502}' => '\u{253c}',
-            _ => '\u{2500}',
-        },
+            unique
+        })
+        .collect()
+}
+
+/// Convert each row into an object, keyed by the header of its column.
+///
+/// Cells without a header are keyed by their column index, repeated headers
+/// are made unique.
+fn json_objects(args: &Args, header: &[String], data: &[Vec<String>]) -> Vec<serde_json::Value> {
+    let columns = data.iter().map(|r| r.len()).fold(header.len(), usize::max);
+    let keys = unique_names(
+        (0..columns)
+            .map(|i| header.get(i).cloned().unwrap_or(i.to_string()))
+            .collect(),
     );
-    let header_overline = replace_with_if(
-        &args.ascii,
-        &header_underline,
-        |c| c,
-        |c| match c {
-            '\u{253c}' => '\u{252c}',
-            x => x,
-        },
+    data.iter()
+        .map(|row| {
+            let object = row
+                .iter()
+                .zip(&keys)
+                .map(|(c, key)| (key.clone(), json_value(args, c)))
+                .collect();
+            serde_json::Value::Object(object)
+        })
+        .collect()
+}
+
+fn escape_latex(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{}", c),
+            '~' => "\\textasciitilde{}".to_string(),
+            '^' => "\\textasciicircum{}".to_string(),
+            '\\' => "\\textbackslash{}".to_string(),
+            '\n' => " ".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Build the column specification of a tabular environment from the layout.
+fn latex_spec(args: &Args, columns: usize) -> String {
+    let align = |i| match column_alignment(args, i) {
+        Alignment::Left => 'l',
+        Alignment::Center => 'c',
+        Alignment::Right => 'r',
+    };
+    let inner: String = match args.layout {
+        Some(ref layout) => (0..columns)
+            .map(|i| {
+                let d = layout.delimiters.get(i).map_or("", |d| d.as_str());
+                format!("{}{}", if i == 0 { "" } else { d }, align(i))
+            })
+            .collect::<String>()
+            .replace(' ', ""),
+        None if args.booktabs => (0..columns).map(align).collect(),
+        None => (0..columns).map(align).join("|"),
+    };
+    if args.decoration == Decoration::Full && !args.booktabs {
+        format!("|{}|", inner)
+    } else {
+        inner
+    }
+}
+
+/// Render a LaTeX tabular environment.
+fn latex(args: &Args, header: &[String], data: &[Vec<String>]) -> String {
+    let escape = |row: &[String]| -> Vec<String> { row.iter().map(|c| escape_latex(c)).collect() };
+    let header = escape(header);
+    let data: Vec<Vec<String>> = data.iter().map(|r| escape(r)).collect();
+    let column_widths = calculate_column_widths(args, &header, &data);
+    let columns = data.iter().map(|r| r.len()).fold(header.len(), usize::max);
+    let (top, mid, bottom) = if args.booktabs {
+        ("\\toprule\n", "\\midrule\n", "\\bottomrule\n")
+    } else {
+        ("\\hline\n", "\\hline\n", "\\hline\n")
+    };
+    let format = |row: &[String]| {
+        format!(
+            "{} \\\\\n",
+            align_row(args, &column_widths, row).join(" & ")
+        )
+    };
+
+    let mut out = format!("\\begin{{tabular}}{{{}}}\n", latex_spec(args, columns));
+    if args.decoration == Decoration::Full {
+        out += top;
+    }
+    out += &format(&header);
+    if args.decoration != Decoration::None {
+        out += mid;
+    }
+    for row in &data {
+        out += &format(row);
+    }
+    if args.decoration == Decoration::Full {
+        out += bottom;
+    }
+    out += "\\end{tabular}\n";
+    out
+}
+
+/// Pad the header and all rows with empty cells to the same number of columns.
+fn rectangular(header: &[String], data: &[Vec<String>]) -> (Vec<String>, Vec<Vec<String>>) {
+    let columns = data.iter().map(|r| r.len()).fold(header.len(), usize::max);
+    let pad = |row: &[String]| -> Vec<String> {
+        let mut row = row.to_vec();
+        row.resize(columns, String::new());
+        row
+    };
+    (pad(header), data.iter().map(|r| pad(r)).collect())
+}
+
+/// Calculate the column widths of a table whose rows all have the same length.
+fn rectangular_widths(args: &Args, header: &[String], data: &[Vec<String>]) -> Vec<usize> {
+    let header = header.to_vec();
+    let mut column_widths = calculate_column_widths(args, &header, data);
+    column_widths.truncate(header.len());
+    column_widths
+}
+
+/// Render a reStructuredText grid table, cells may span multiple lines.
+fn rst_grid(args: &Args, header: &[String], data: &[Vec<String>]) -> String {
+    let (header, data) = rectangular(header, data);
+    let lines: Vec<Vec<String>> = std::iter::once(&header)
+        .chain(&data)
+        .flat_map(|r| split_multiline(r))
+        .collect();
+    let column_widths: Vec<usize> = rectangular_widths(args, &header, &lines)
+        .iter()
+        .map(|w| usize::max(*w, 1))
+        .collect();
+    let rule = |c: &str| {
+        format!(
+            "+{}+\n",
+            column_widths.iter().map(|w| c.repeat(w + 2)).join("+")
+        )
+    };
+    let format = |row: &Vec<String>| -> String {
+        split_multiline(row)
+            .iter()
+            .map(|l| format!("| {} |\n", align_row(args, &column_widths, l).join(" | ")))
+            .collect()
+    };
+    let mut out = rule("-");
+    out += &format(&header);
+    out += &rule("=");
+    for row in &data {
+        out += &format(row);
+        out += &rule("-");
+    }
+    out
+}
+
+/// Render a reStructuredText simple table.
+fn rst_simple(args: &Args, header: &[String], data: &[Vec<String>]) -> String {
+    let single_line = |row: &[String]| -> Vec<String> {
+        row.iter()
+            .enumerate()
+            .map(|(i, c)| match c.split_whitespace().join(" ") {
+                // an empty first cell would turn the row into a continuation line
+                c if c.is_empty() && i == 0 => "\\".to_string(),
+                c => c,
+            })
+            .collect()
+    };
+    let (header, data) = rectangular(header, data);
+    let header = single_line(&header);
+    let data: Vec<Vec<String>> = data.iter().map(|r| single_line(r)).collect();
+    let column_widths: Vec<usize> = rectangular_widths(args, &header, &data)
+        .iter()
+        .map(|w| usize::max(*w, 1))
+        .collect();
+    let rule = format!(
+        "{}\n",
+        column_widths.iter().map(|w| "=".repeat(*w)).join("  ")
     );
-    let footer_line = replace_with_if(
-        &args.ascii,
-        &header_underline,
-        |c| c,
-        |c| match c {
-            '\u{253c}' => '\u{2534}',
-            x => x,
-        },
+    let format = |row: &[String]| {
+        format!(
+            "{}\n",
+            align_row(args, &column_widths, row).join("  ").trim_end()
+        )
+    };
+    let mut out = rule.clone();
+    out += &format(&header);
+    out += &rule;
+    for row in &data {
+        out += &format(row);
+    }
+    out += &rule;
+    out
+}
+
+/// Render an AsciiDoc table.
+fn asciidoc(args: &Args, header: &[String], data: &[Vec<String>]) -> String {
+    let escape = |row: &[String]| -> Vec<String> {
+        row.iter()
+            .map(|c| c.replace('|', "\\|").split_whitespace().join(" "))
+            .collect()
+    };
+    let (header, data) = rectangular(header, data);
+    let header = escape(&header);
+    let data: Vec<Vec<String>> = data.iter().map(|r| escape(r)).collect();
+    let column_widths = rectangular_widths(args, &header, &data);
+    let format = |row: &[String]| {
+        format!(
+            "| {}\n",
+            align_row(args, &column_widths, row).join(" | ").trim_end()
+        )
+    };
+    let mut out = match args.layout {
+        Some(_) => {
+            let cols = (0..header.len())
+                .map(|i| match column_alignment(args, i) {
+                    Alignment::Left => "<",
+                    Alignment::Center => "^",
+                    Alignment::Right => ">",
+                })
+                .join(",");
+            format!("[cols=\"{}\",options=\"header\"]\n", cols)
+        }
+        None => "[options=\"header\"]\n".to_string(),
+    };
+    out += "|===\n";
+    out += &format(&header);
+    for row in &data {
+        out += &format(row);
+    }
+    out += "|===\n";
+    out
+}
+
+/// Render an Org mode table.
+fn org(args: &Args, header: &[String], data: &[Vec<String>]) -> String {
+    let escape = |row: &[String]| -> Vec<String> {
+        row.iter()
+            .map(|c| c.replace('|', "\\vert{}").split_whitespace().join(" "))
+            .collect()
+    };
+    let (header, data) = rectangular(header, data);
+    let header = escape(&header);
+    let data: Vec<Vec<String>> = data.iter().map(|r| escape(r)).collect();
+    let column_widths = rectangular_widths(args, &header, &data);
+    let rule = format!(
+        "|{}|\n",
+        column_widths.iter().map(|w| "-".repeat(w + 2)).join("+")
     );
-    let print_a_u = |al, ar, ul, ur, s| {
-        if args.ascii {
-            println!("{}{}{}", al, s, ar);
-        } else {
-            println!("{}{}{}", ul, s, ur);
+    let format =
+        |row: &[String]| format!("| {} |\n", align_row(args, &column_widths, row).join(" | "));
+    let mut out = String::new();
+    if args.decoration == Decoration::Full {
+        out += &rule;
+    }
+    out += &format(&header);
+    if args.decoration != Decoration::None {
+        out += &rule;
+    }
+    for row in &data {
+        out += &format(row);
+    }
+    if args.decoration == Decoration::Full {
+        out += &rule;
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SqlType {
+    Integer,
+    Real,
+    Boolean,
+    Text,
+}
+
+impl SqlType {
+    fn of(cell: &str) -> Self {
+        match serde_json::from_str::<serde_json::Number>(cell) {
+            Ok(n) if n.is_i64() || n.is_u64() => SqlType::Integer,
+            Ok(_) => SqlType::Real,
+            _ if cell == "true" || cell == "false" => SqlType::Boolean,
+            _ => SqlType::Text,
+        }
+    }
+
+    /// The narrowest type which can hold the values of both types.
+    fn union(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (SqlType::Integer, SqlType::Real) | (SqlType::Real, SqlType::Integer) => SqlType::Real,
+            _ => SqlType::Text,
         }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            SqlType::Integer => "INTEGER",
+            SqlType::Real => "REAL",
+            SqlType::Boolean => "BOOLEAN",
+            SqlType::Text => "TEXT",
+        }
+    }
+}
+
+fn quote_identifier(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+/// Render a CREATE TABLE statement followed by batched INSERT statements.
+///
+/// Empty cells become NULL and are ignored when inferring the column types.
+/// Columns without a header are named by their index, repeated headers are
+/// made unique.
+fn sql(args: &Args, header: &[String], data: &[Vec<String>]) -> String {
+    let (header, data) = rectangular(header, data);
+    let types: Vec<SqlType> = (0..header.len())
+        .map(|i| {
+            data.iter()
+                .map(|r| &r[i])
+                .filter(|c| !c.is_empty())
+                .map(|c| SqlType::of(c))
+                .reduce(SqlType::union)
+                .unwrap_or(SqlType::Text)
+        })
+        .collect();
+    let names: Vec<String> = unique_names(
+        header
+            .iter()
+            .enumerate()
+            .map(|(i, h)| match h.as_str() {
+                "" => format!("column_{}", i),
+                h => h.to_string(),
+            })
+            .collect(),
+    )
+    .iter()
+    .map(|n| quote_identifier(n))
+    .collect();
+    let table = quote_identifier(&args.table_name);
+
+    let mut out = format!("CREATE TABLE {} (\n", table);
+    out += &names
+        .iter()
+        .zip(&types)
+        .map(|(n, t)| format!("    {} {}", n, t.name()))
+        .join(",\n");
+    out += "\n);\n";
+    for batch in data.chunks(args.batch_size) {
+        out += &format!("INSERT INTO {} ({}) VALUES\n", table, names.join(", "));
+        out += &batch
+            .iter()
+            .map(|row| {
+                let mut values = row.iter().zip(&types).map(|(c, t)| match t {
+                    _ if c.is_empty() => "NULL".to_string(),
+                    SqlType::Text => format!("'{}'", c.replace('\'', "''")),
+                    SqlType::Boolean => c.to_uppercase(),
+                    _ => c.clone(),
+                });
+                format!("    ({})", values.join(", "))
+            })
+            .join(",\n");
+        out += ";\n";
+    }
+    out
+}
+
+/// Print the rows in the output format, the first row being the header.
+///
+/// `layout` is the layout of the input, it is used unless one is given on the
+/// command line.
+pub fn display(args: Args, rows: &Vec<Vec<String>>, layout: Option<ColumnLayout>) {
+    if rows.len() == 0 {
+        return;
+    }
+    let args = Args {
+        layout: args.layout.or(layout),
+        ..args
     };
-    let print_f_a_u = |al, ar, ul, ur, s| {
+    let (header, data) = (rows[0].clone(), &rows[1..]);
+
+    match args.output_format {
+        OutputFormat::Table => display_table(&args, header, data),
+        OutputFormat::Markdown => print!("{}", markdown(&args, &header, data)),
+        OutputFormat::Html => print!("{}", html(&args, &header, data)),
+        OutputFormat::Csv => print!("{}", delimited(&header, data, ',')),
+        OutputFormat::Tsv => print!("{}", delimited(&header, data, '\t')),
+        OutputFormat::Json => {
+            let objects = json_objects(&args, &header, data);
+            println!("{}", serde_json::to_string_pretty(&objects).unwrap());
+        }
+        OutputFormat::Ndjson => {
+            for object in json_objects(&args, &header, data) {
+                println!("{}", object);
+            }
+        }
+        OutputFormat::Latex => print!("{}", latex(&args, &header, data)),
+        OutputFormat::RstGrid => print!("{}", rst_grid(&args, &header, data)),
+        OutputFormat::RstSimple => print!("{}", rst_simple(&args, &header, data)),
+        OutputFormat::Asciidoc => print!("{}", asciidoc(&args, &header, data)),
+        OutputFormat::Org => print!("{}", org(&args, &header, data)),
+        OutputFormat::Sql => print!("{}", sql(&args, &header, data)),
+    }
+}
+
+fn display_table(args: &Args, header: Vec<String>, data: &[Vec<String>]) {
+    let border = border(args);
+    let header_lines = split_multiline(&header);
+    let data: Vec<Vec<String>> = data.iter().flat_map(|r| split_multiline(r)).collect();
+    let column_widths = calculate_column_widths(
+        args,
+        &header_lines[0],
+        &[&header_lines[1..], &data[..]].concat(),
+    );
+    let rule = |junction, fill| format_rule(args, &column_widths, header.len(), junction, fill);
+    let header_underline = rule(border.header_cross, border.header);
+    let header_overline = rule(border.top_tee, border.horizontal);
+    let footer_line = rule(border.bottom_tee, border.horizontal);
+    let print_f = |l, r, s| {
         if args.decoration == Decoration::Full {
-            print_a_u(al, ar, ul, ur, s);
+            println!("{}{}{}", l, s, r);
         } else {
             println!("{}", s);
         }
     };
 
     if args.decoration == Decoration::Full {
-        print_a_u("+", "+", "\u{250c}", "\u{2510}", header_overline);
+        print_f(border.top_left, border.top_right, header_overline);
+    }
+    for line in &header_lines {
+        let t = format_row(args, &column_widths, line);
+        print_f(border.vertical, border.vertical, t);
     }
-    print_f_a_u("|", "|", "\u{2502}", "\u{2502}", header_text);
 
     if args.decoration != Decoration::None {
-        print_f_a_u("+", "+", "\u{251c}", "\u{2524}", header_underline);
+        print_f(border.header_left, border.header_right, header_underline);
     }
-    for row in data {
-        let t = format_row(&args, &column_widths, row);
-        print_f_a_u("|", "|", "\u{2502}", "\u{2502}", t);
+    for row in &data {
+        let t = format_row(args, &column_widths, row);
+        print_f(border.vertical, border.vertical, t);
     }
 
     if args.decoration == Decoration::Full {
-        print_a_u("+", "+", "\u{2514}", "\u{2518}", footer_line);
+        print_f(border.bottom_left, border.bottom_right, footer_line);
+    }
+}
+
+mod test {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use clap::Parser;
+
+    #[allow(dead_code)]
+    fn strings(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|r| r.iter().map(|c| c.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_markdown() {
+        let header = strings(&[&["Name", "Op", "N"]]).remove(0);
+        let data = strings(&[&["a", "|", "1"], &["bb", "x\ny", "100"]]);
+        let args = Args::parse_from(["tbl"]);
+        assert_eq!(
+            markdown(&args, &header, &data),
+            "\
+| Name | Op     | N   |
+| ---- | ------ | --- |
+| a    | \\|     | 1   |
+| bb   | x<br>y | 100 |
+"
+        );
+        let args = Args::parse_from(["tbl", "-l", "l|c|r"]);
+        assert_eq!(
+            markdown(&args, &header, &data),
+            "\
+| Name |   Op   |   N |
+| :--- | :----: | --: |
+| a    |   \\|   |   1 |
+| bb   | x<br>y | 100 |
+"
+        );
+    }
+
+    #[test]
+    fn test_html() {
+        let header = strings(&[&["Name", "N"]]).remove(0);
+        let data = strings(&[&["<a & b>", "1"], &["x\ny", "\"2\""]]);
+        let args = Args::parse_from(["tbl", "-o", "html"]);
+        assert_eq!(
+            html(&args, &header, &data),
+            "\
+<table>
+<thead>
+<tr><th>Name</th><th>N</th></tr>
+</thead>
+<tbody>
+<tr><td>&lt;a &amp; b&gt;</td><td>1</td></tr>
+<tr><td>x<br>y</td><td>&quot;2&quot;</td></tr>
+</tbody>
+</table>
+"
+        );
+        let args = Args::parse_from(["tbl", "-o", "html", "-l", "c|r", "--standalone"]);
+        let out = html(&args, &header, &data[..1]);
+        assert!(out.starts_with("<!DOCTYPE html>\n"));
+        assert!(out.contains(
+            "<tr><th style=\"text-align: center\">Name</th><th style=\"text-align: right\">N</th></tr>"
+        ));
+        assert!(out.ends_with("</table>\n</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn test_delimited() {
+        let header = strings(&[&["Name", "Note"]]).remove(0);
+        let data = strings(&[
+            &["Doe, John", "say \"hi\""],
+            &["a\tb", "x\ny"],
+            &["", "plain"],
+        ]);
+        assert_eq!(
+            delimited(&header, &data, ','),
+            "Name,Note\n\"Doe, John\",\"say \"\"hi\"\"\"\na\tb,\"x\ny\"\n,plain\n"
+        );
+        assert_eq!(
+            delimited(&header, &data, '\t'),
+            "Name\tNote\nDoe, John\t\"say \"\"hi\"\"\"\n\"a\tb\"\t\"x\ny\"\n\tplain\n"
+        );
+    }
+
+    #[test]
+    fn test_json_objects() {
+        let header = strings(&[&["id", "ok", "name"]]).remove(0);
+        let data = strings(&[&["1", "true", "007"], &["-2.5e3", "no", "x", "extra"]]);
+        let args = Args::parse_from(["tbl", "-o", "json"]);
+        assert_eq!(
+            serde_json::Value::Array(json_objects(&args, &header, &data)).to_string(),
+            r#"[{"id":"1","ok":"true","name":"007"},{"id":"-2.5e3","ok":"no","name":"x","3":"extra"}]"#
+        );
+        let args = Args::parse_from(["tbl", "-o", "json", "--infer-types"]);
+        assert_eq!(
+            serde_json::Value::Array(json_objects(&args, &header, &data)).to_string(),
+            r#"[{"id":1,"ok":true,"name":"007"},{"id":-2500.0,"ok":"no","name":"x","3":"extra"}]"#
+        );
+        let header = strings(&[&["a", "a", "a_1", "a"]]).remove(0);
+        let data = strings(&[&["1", "2", "3", "4"]]);
+        assert_eq!(
+            serde_json::Value::Array(json_objects(&args, &header, &data)).to_string(),
+            r#"[{"a":1,"a_1":2,"a_1_1":3,"a_2":4}]"#
+        );
+    }
+
+    #[test]
+    fn test_latex() {
+        let header = strings(&[&["Item", "Cost"]]).remove(0);
+        let data = strings(&[&["A & B_1", "50%"], &["{x}", "$3"]]);
+        let args = Args::parse_from(["tbl", "-o", "latex"]);
+        assert_eq!(
+            latex(&args, &header, &data),
+            r"\begin{tabular}{l|l}
+Item      & Cost \\
+\hline
+A \& B\_1 & 50\% \\
+\{x\}     & \$3  \\
+\end{tabular}
+"
+        );
+        let args = Args::parse_from(["tbl", "-o", "latex", "-l", "l || r", "--decoration", "full"]);
+        assert_eq!(
+            latex(&args, &header, &data[..1]),
+            r"\begin{tabular}{|l||r|}
+\hline
+Item      & Cost \\
+\hline
+A \& B\_1 & 50\% \\
+\hline
+\end{tabular}
+"
+        );
+        let args = Args::parse_from(["tbl", "-o", "latex", "--decoration", "full", "--booktabs"]);
+        assert_eq!(
+            latex(&args, &header, &data[1..]),
+            r"\begin{tabular}{ll}
+\toprule
+Item  & Cost \\
+\midrule
+\{x\} & \$3  \\
+\bottomrule
+\end{tabular}
+"
+        );
+        assert_eq!(
+            escape_latex(r"~^\"),
+            r"\textasciitilde{}\textasciicircum{}\textbackslash{}"
+        );
+    }
+
+    #[test]
+    fn test_rst() {
+        let header = strings(&[&["Name", "N"]]).remove(0);
+        let data = strings(&[&["a\nb", "1"], &["", "22", "x"]]);
+        let args = Args::parse_from(["tbl", "-o", "rst", "-l", "l|r"]);
+        assert_eq!(
+            rst_grid(&args, &header, &data),
+            "\
++------+----+---+
+| Name |  N |   |
++======+====+===+
+| a    |  1 |   |
+| b    |    |   |
++------+----+---+
+|      | 22 | x |
++------+----+---+
+"
+        );
+        assert_eq!(
+            rst_simple(&args, &header, &data),
+            "\
+====  ==  =
+Name   N
+====  ==  =
+a b    1
+\\     22  x
+====  ==  =
+"
+        );
+    }
+
+    #[test]
+    fn test_asciidoc() {
+        let header = strings(&[&["Name", "Op"]]).remove(0);
+        let data = strings(&[&["or", "|"], &["and"]]);
+        let args = Args::parse_from(["tbl", "-o", "adoc"]);
+        assert_eq!(
+            asciidoc(&args, &header, &data),
+            "\
+[options=\"header\"]
+|===
+| Name | Op
+| or   | \\|
+| and  |
+|===
+"
+        );
+        let args = Args::parse_from(["tbl", "-o", "adoc", "-l", "r c"]);
+        assert!(asciidoc(&args, &header, &data).starts_with("[cols=\">,^\",options=\"header\"]\n"));
+    }
+
+    #[test]
+    fn test_org() {
+        let header = strings(&[&["Name", "Op"]]).remove(0);
+        let data = strings(&[&["or", "|"], &["and"]]);
+        let args = Args::parse_from(["tbl", "-o", "org"]);
+        assert_eq!(
+            org(&args, &header, &data),
+            "\
+| Name | Op      |
+|------+---------|
+| or   | \\vert{} |
+| and  |         |
+"
+        );
+        let args = Args::parse_from(["tbl", "-o", "org", "--decoration", "full"]);
+        assert!(org(&args, &header, &data).starts_with("|------+---------|\n| Name |"));
+        let args = Args::parse_from(["tbl", "-o", "org", "--decoration", "none"]);
+        assert_eq!(org(&args, &header, &data).lines().count(), 3);
+    }
+
+    #[test]
+    fn test_sql_type() {
+        assert_eq!(SqlType::of("42"), SqlType::Integer);
+        assert_eq!(SqlType::of("-1.5e3"), SqlType::Real);
+        assert_eq!(SqlType::of("007"), SqlType::Text);
+        assert_eq!(SqlType::of("true"), SqlType::Boolean);
+        assert_eq!(SqlType::Integer.union(SqlType::Real), SqlType::Real);
+        assert_eq!(SqlType::Boolean.union(SqlType::Integer), SqlType::Text);
+    }
+
+    #[test]
+    fn test_sql() {
+        let header = strings(&[&["id", "name", "score", ""]]).remove(0);
+        let data = strings(&[
+            &["1", "O'Brien", "2.5", "true"],
+            &["2", "", "3"],
+            &["3", "x", "", "false"],
+        ]);
+        let args = Args::parse_from([
+            "tbl",
+            "-o",
+            "sql",
+            "--table-name",
+            "people",
+            "--batch-size",
+            "2",
+        ]);
+        assert_eq!(
+            sql(&args, &header, &data),
+            r#"CREATE TABLE "people" (
+    "id" INTEGER,
+    "name" TEXT,
+    "score" REAL,
+    "column_3" BOOLEAN
+);
+INSERT INTO "people" ("id", "name", "score", "column_3") VALUES
+    (1, 'O''Brien', 2.5, TRUE),
+    (2, NULL, 3, NULL);
+INSERT INTO "people" ("id", "name", "score", "column_3") VALUES
+    (3, 'x', NULL, FALSE);
+"#
+        );
+        let header = strings(&[&["a", "a", ""]]).remove(0);
+        let data = strings(&[&["1", "x", "2"]]);
+        assert!(sql(&args, &header, &data).starts_with(
+            r#"CREATE TABLE "people" (
+    "a" INTEGER,
+    "a_1" TEXT,
+    "column_2" INTEGER
+);
+INSERT INTO "people" ("a", "a_1", "column_2") VALUES
+"#
+        ));
+    }
+
+    #[test]
+    fn test_format_rule() {
+        let column_widths = vec![3, 5, 2];
+        let header = strings(&[&["a|b", "c \u{2502} d", "e"]]).remove(0);
+        let args = Args::parse_from(["tbl"]);
+        assert_eq!(
+            format_row(&args, &column_widths, &header),
+            "a|b\u{2502}c \u{2502} d\u{2502}e "
+        );
+        assert_eq!(
+            format_rule(&args, &column_widths, 3, '\u{253c}', '\u{2500}'),
+            "───┼─────┼──"
+        );
+        let args = Args::parse_from(["tbl", "-a", "-l", "|l || c r|"]);
+        assert_eq!(
+            format_row(&args, &column_widths, &header),
+            "|a|b || c │ d  e|"
+        );
+        assert_eq!(
+            format_rule(&args, &column_widths, 3, '+', '-'),
+            "+----++---------+"
+        );
+        assert_eq!(
+            format_rule(&args, &column_widths, 2, '+', '-'),
+            "+----++-------+"
+        );
+        let args = Args::parse_from(["tbl", "--theme", "psql"]);
+        assert_eq!(
+            format_row(&args, &column_widths, &header),
+            " a|b | c │ d | e  "
+        );
+        assert_eq!(
+            format_rule(&args, &column_widths, 3, '+', '-'),
+            "-----+-------+----"
+        );
     }
 }
@@ -6,6 +6,7 @@ use std::process::exit;
 use std::str::FromStr;
 
 use crate::arguments::*;
+use crate::input::*;
 
 fn get_sort_comparator(
     sort_by: &Option<Vec<SortOrder>>,
@@ -117,26 +118,235 @@ fn map_column(mapping: &ColumnMapping, cols: &Vec<String>) -> String {
     }
 }
 
-fn read_inputs(delimiter: &String, input: Box<dyn BufRead>) -> Vec<Vec<String>> {
-    let input_matrix = input
+/// Split delimited input into records, dropping those which are empty or
+/// comments.
+///
+/// Records are filtered after splitting, since comments and empty lines may
+/// also occur inside of quoted fields, where they are part of the field.
+fn read_delimited(args: &Args, lines: &[String]) -> Table {
+    let delimiter = match args.delimiter {
+        Some(ref d) if args.regex_delimiter => Delimiter::regex(d).unwrap_or_else(|e| {
+            eprintln!("Invalid delimiter: {}", e);
+            exit(1);
+        }),
+        Some(ref d) => Delimiter::Literal(d.clone()),
+        None => {
+            let sample = lines.iter().filter(|l| !is_skipped(args, l));
+            let d = sniff_delimiter(sample, !args.no_quotes);
+            if args.verbose {
+                eprintln!("Detected delimiter: {}", d);
+            }
+            d
+        }
+    };
+    let delimiter = if args.collapse_delimiters {
+        delimiter.collapsed()
+    } else {
+        delimiter
+    };
+    let table = parse_delimited(
+        lines.iter().cloned(),
+        &delimiter,
+        !args.no_quotes,
+        args.max_columns,
+    );
+    let starts: Vec<usize> = table.lines.iter().map(|l| l.unwrap_or(0)).collect();
+    let ends = starts.iter().skip(1).copied().chain([lines.len()]);
+    let (rows, lines): (Vec<Vec<String>>, Vec<Option<usize>>) = table
+        .rows
+        .into_iter()
+        .zip(table.lines)
+        .zip(starts.iter().zip(ends))
+        .filter(|(_, (start, end))| !is_skipped(args, &lines[**start..*end].join("\n")))
+        .map(|(row, _)| row)
+        .unzip();
+    Table {
+        rows,
+        lines,
+        ..table
+    }
+}
+
+/// Whether a line or record of input should be ignored, because it is empty or
+/// a comment.
+fn is_skipped(args: &Args, line: &str) -> bool {
+    (args.skip_empty && line.trim().is_empty())
+        || args
+            .comment
+            .as_ref()
+            .is_some_and(|c| line.trim_start().starts_with(c.as_str()))
+        || args
+            .comment_regex
+            .as_ref()
+            .is_some_and(|re| re.is_match(line))
+}
+
+fn read_inputs(args: &Args, mut input: Box<dyn BufRead>) -> Table {
+    let mut bytes = vec![];
+    if let Err(e) = input.read_to_end(&mut bytes) {
+        eprintln!("Failed to read input: {}", e);
+        exit(1);
+    }
+    let text = decode(&bytes, &args.encoding).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(1);
+    });
+    // Delimited input is filtered by record instead, see read_delimited
+    let by_record = args.pattern.is_none() && args.input_format == InputFormat::Delimited;
+    let (line_numbers, lines): (Vec<usize>, Vec<String>) = text
         .lines()
-        .map(|l| l.unwrap())
-        .map(|l| {
-            l.split(delimiter)
-                .map(|c| c.to_string())
-                .collect::<Vec<String>>()
+        .enumerate()
+        .skip(args.skip_lines)
+        .filter(|(_, l)| by_record || !is_skipped(args, l))
+        .map(|(i, l)| (i + 1, l.to_string()))
+        .unzip();
+    let mut table = match args.pattern {
+        Some(ref pattern) => {
+            let (table, unmatched) = parse_pattern(lines.into_iter(), pattern);
+            if args.unmatched == Unmatched::Report {
+                for (i, line) in unmatched {
+                    eprintln!(
+                        "Line {} does not match the pattern: {}",
+                        line_numbers[i], line
+                    );
+                }
+            }
+            table
+        }
+        None => match args.input_format {
+            InputFormat::Delimited => read_delimited(args, &lines),
+            InputFormat::Json => {
+                parse_json(&lines.join("\n"), &args.json_arrays).unwrap_or_else(|e| {
+                    eprintln!("Failed to parse JSON: {}", e);
+                    exit(1);
+                })
+            }
+            InputFormat::Ndjson => {
+                let (table, errors) = parse_ndjson(lines.into_iter(), &args.json_arrays);
+                for (i, e) in errors {
+                    eprintln!("Line {}: {}", line_numbers[i], e);
+                }
+                table
+            }
+            InputFormat::Markdown => parse_markdown(lines.into_iter()),
+            InputFormat::FixedWidth => parse_fixed_width(lines.into_iter()),
+            InputFormat::Logfmt => parse_logfmt(lines.into_iter()),
+            InputFormat::Records => {
+                parse_records(lines.into_iter(), args.delimiter.as_deref().unwrap_or(":"))
+            }
+        },
+    };
+    table.lines = table
+        .lines
+        .iter()
+        .map(|l| l.map(|i| line_numbers[i]))
+        .collect();
+    match args.header_line {
+        Some(n) if table.header.is_none() => {
+            let mut rows = table.rows.into_iter().skip(n);
+            Table {
+                header: rows.next(),
+                rows: rows.collect(),
+                alignment: table.alignment,
+                lines: table.lines.into_iter().skip(n + 1).collect(),
+            }
+        }
+        _ => table,
+    }
+}
+
+/// Read all inputs and combine them into a single table.
+///
+/// If there are multiple inputs and no headers are given on the command line,
+/// the columns of the inputs are matched up by their headers.
+fn read_all_inputs(args: &Args, inputs: Vec<(String, Box<dyn BufRead>)>) -> Table {
+    let match_headers = inputs.len() > 1 && args.headers.is_none();
+    // The source column needs a header, which cannot be the first row
+    let split_header = args.headers.is_none() && (match_headers || args.source_column);
+    let (names, mut tables): (Vec<String>, Vec<Table>) = inputs
+        .into_iter()
+        .map(|(name, input)| (name, read_inputs(args, input)))
+        .unzip();
+    for table in tables.iter_mut() {
+        if split_header && table.header.is_none() && !table.rows.is_empty() {
+            table.header = Some(table.rows.remove(0));
+            table.lines.remove(0);
+        }
+        // Cells beyond the header can only be matched up by position
+        let width = table.rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        if let Some(h) = table.header.as_mut().filter(|_| match_headers) {
+            h.resize(usize::max(h.len(), width), "".to_string());
+        }
+    }
+    let sources: Vec<String> = tables
+        .iter()
+        .zip(&names)
+        .flat_map(|(t, name)| {
+            t.lines.iter().map(move |line| match line {
+                Some(l) => format!("{}:{}", name, l),
+                None => name.clone(),
+            })
         })
-        .collect::<Vec<_>>();
-    if input_matrix.len() == 0 {
-        exit(0);
+        .collect();
+    let mut table = if match_headers {
+        Table::union(tables)
+    } else {
+        let mut tables = tables.into_iter();
+        let mut table = tables.next().unwrap();
+        for t in tables {
+            table.rows.extend(t.rows);
+            table.lines.extend(t.lines);
+        }
+        table
+    };
+    if args.source_column {
+        // Without a column mapping, the source column follows the named columns
+        let width = args
+            .headers
+            .as_ref()
+            .filter(|_| args.columns.is_none())
+            .map(|h| h.len());
+        add_source_column(&mut table, sources, width);
+    }
+    table
+}
+
+/// Append a column containing the name of the input and the line number each
+/// row was read from, after padding or cutting all rows to `width`, or to the
+/// same width if it is not given.
+fn add_source_column(table: &mut Table, sources: Vec<String>, width: Option<usize>) {
+    let width = width.unwrap_or_else(|| {
+        table
+            .rows
+            .iter()
+            .chain(table.header.iter())
+            .map(|r| r.len())
+            .max()
+            .unwrap_or(0)
+    });
+    if let Some(h) = table.header.as_mut() {
+        h.resize(width, "".to_string());
+        h.push("source".to_string());
+    }
+    for (row, source) in table.rows.iter_mut().zip(sources) {
+        row.resize(width, "".to_string());
+        row.push(source);
+    }
+}
+
+/// The header of the source column, if it is not named by --headers.
+fn source_column_header(args: &Args) -> Vec<String> {
+    if args.source_column && args.columns.is_none() {
+        vec!["source".to_string()]
+    } else {
+        vec![]
     }
-    input_matrix
 }
 
 fn get_number_of_columns(args: &Args, input_length: usize) -> usize {
     let mut widths = vec![input_length];
     if let Some(h) = &args.headers {
-        widths.push(h.len());
+        widths.push(h.len() + source_column_header(args).len());
     };
     if let Some(c) = &args.columns {
         widths.push(c.len());
@@ -150,17 +360,57 @@ fn get_number_of_columns(args: &Args, input_length: usize) -> usize {
     *widths.iter().min().unwrap()
 }
 
-#[allow(unstable_name_collisions)]
-pub fn process(args: &Args, input: Box<dyn BufRead>) -> Vec<Vec<String>> {
-    let sort_comparator = get_sort_comparator(&args.sort_by);
+/// Alignment of the output columns, given the alignment of the input columns.
+///
+/// Output columns which are not mapped to exactly one input column are aligned
+/// to the left.
+fn map_alignment(args: &Args, alignment: &[Alignment]) -> Vec<Alignment> {
+    let n_columns = match args.columns {
+        Some(ref cms) => cms.len(),
+        None => alignment.len(),
+    };
+    (0..n_columns)
+        .map(|i| {
+            let source = match args.columns {
+                Some(ref cms) => match cms[i] {
+                    ColumnMapping::Index(j) => absolute_index(alignment.len(), &j),
+                    _ => usize::MAX,
+                },
+                None => i,
+            };
+            alignment.get(source).unwrap_or(&Alignment::Left).clone()
+        })
+        .collect()
+}
 
-    let mut input_matrix = read_inputs(&args.delimiter, input);
-    if input_matrix.len() == 0 {
+/// Read, sort and map the inputs to the rows of the output, the first of which
+/// is the header.
+///
+/// If the input specifies the alignment of its columns, it is returned as the
+/// layout of the output columns.
+#[allow(unstable_name_collisions)]
+pub fn process(
+    args: &Args,
+    inputs: Vec<(String, Box<dyn BufRead>)>,
+) -> (Vec<Vec<String>>, Option<ColumnLayout>) {
+    let Table {
+        header,
+        rows: mut input_matrix,
+        alignment,
+        ..
+    } = read_all_inputs(args, inputs);
+    if input_matrix.is_empty() {
         exit(0);
     }
+    let sort_comparator = get_sort_comparator(&args.sort_by);
+
+    // The first row can only be a header if the input did not provide one
+    let ignore_first = args.sort_ignore_first && header.is_none();
+    // Headers given on the command line take precedence over those of the input
+    let header = header.filter(|_| args.headers.is_none());
 
     if args.sort && !args.sort_by_output {
-        if args.sort_ignore_first {
+        if ignore_first {
             input_matrix[1..].sort_by(&sort_comparator);
         } else {
             input_matrix.sort_by(&sort_comparator);
@@ -169,10 +419,28 @@ pub fn process(args: &Args, input: Box<dyn BufRead>) -> Vec<Vec<String>> {
 
     let n_columns = get_number_of_columns(
         &args,
-        input_matrix.iter().map(|x| x.len()).max().unwrap_or(0),
+        input_matrix
+            .iter()
+            .chain(header.iter())
+            .map(|x| x.len())
+            .max()
+            .unwrap_or(0),
     );
+    // Output columns without an alignment in the input, like the source
+    // column, are aligned to the left
+    let layout = alignment.map(|a| {
+        let mut column_align = map_alignment(args, &a);
+        column_align.resize(n_columns, Alignment::Left);
+        let mut delimiters = vec!["|".to_string(); n_columns + 1];
+        delimiters[0] = "".to_string();
+        delimiters[n_columns] = "".to_string();
+        ColumnLayout {
+            column_align,
+            delimiters,
+        }
+    });
 
-    let output_matrix = input_matrix.iter().map(|cols| {
+    let map_row = |cols: &Vec<String>| {
         if let Some(cms) = &args.columns {
             cms.iter()
                 .map(|cm| map_column(cm, cols))
@@ -180,7 +448,8 @@ pub fn process(args: &Args, input: Box<dyn BufRead>) -> Vec<Vec<String>> {
         } else {
             cols.clone()
         }
-    });
+    };
+    let output_matrix = input_matrix.iter().map(map_row);
 
     let mut output_matrix: Vec<Vec<_>> = if args.unique {
         output_matrix.unique().collect()
@@ -189,25 +458,99 @@ pub fn process(args: &Args, input: Box<dyn BufRead>) -> Vec<Vec<String>> {
     };
 
     if args.sort && args.sort_by_output {
-        if args.sort_ignore_first {
+        if ignore_first {
             output_matrix[1..].sort_by(&sort_comparator);
         } else {
             output_matrix.sort_by(&sort_comparator);
         }
     }
 
+    match (&args.headers, header) {
+        (Some(h), _) => output_matrix.insert(0, [h.clone(), source_column_header(args)].concat()),
+        (None, Some(ref h)) => output_matrix.insert(0, map_row(h)),
+        (None, None) => {}
+    }
+
     output_matrix.iter_mut().for_each(|r| {
         r.drain(usize::min(n_columns, r.len())..);
         while r.len() < n_columns {
             r.push("".to_string())
         }
     });
-    output_matrix
+    (output_matrix, layout)
 }
 
 mod test {
     #[allow(unused_imports)]
     use super::*;
+    #[allow(unused_imports)]
+    use clap::Parser;
+    #[allow(unused_imports)]
+    use regex::Regex;
+
+    #[test]
+    fn test_map_alignment() {
+        let mut args = Args::parse_from(["tbl"]);
+        let alignment = vec![Alignment::Right, Alignment::Center];
+        assert_eq!(map_alignment(&args, &alignment), alignment);
+        args.columns = Some(vec![
+            ColumnMapping::Index(-1),
+            ColumnMapping::List(vec![0, 1], " ".to_string()),
+            ColumnMapping::Index(0),
+            ColumnMapping::Index(5),
+        ]);
+        assert_eq!(
+            map_alignment(&args, &alignment),
+            vec![
+                Alignment::Center,
+                Alignment::Left,
+                Alignment::Right,
+                Alignment::Left
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_skipped() {
+        let mut args = Args::parse_from(["tbl"]);
+        assert!(!is_skipped(&args, ""));
+        assert!(!is_skipped(&args, "# comment"));
+        args.skip_empty = true;
+        args.comment = Some("#".to_string());
+        args.comment_regex = Some(Regex::new("^--").unwrap());
+        assert!(is_skipped(&args, "  \t"));
+        assert!(is_skipped(&args, "  # comment"));
+        assert!(is_skipped(&args, "-- comment"));
+        assert!(!is_skipped(&args, "a,# b"));
+        assert!(!is_skipped(&args, " -- b"));
+    }
+
+    #[test]
+    fn test_read_inputs_skipped_in_quotes() {
+        let text = "# preamble\nid,note\n\n1,\"line\n\n# not a comment\"\n# comment\n2,x\n";
+        let args = Args::parse_from(["tbl", "--comment", "#", "--skip-empty"]);
+        let table = read_inputs(&args, Box::new(std::io::Cursor::new(text)));
+        assert_eq!(
+            table.rows,
+            vec![
+                vec!["id", "note"],
+                vec!["1", "line\n\n# not a comment"],
+                vec!["2", "x"],
+            ]
+        );
+        assert_eq!(table.lines, vec![Some(2), Some(4), Some(8)]);
+        let args = Args::parse_from(["tbl", "--skip-lines", "2", "--comment-regex", "^#"]);
+        let table = read_inputs(&args, Box::new(std::io::Cursor::new(text)));
+        assert_eq!(
+            table.rows,
+            vec![
+                vec![""],
+                vec!["1", "line\n\n# not a comment"],
+                vec!["2", "x"]
+            ]
+        );
+        assert_eq!(table.lines, vec![Some(3), Some(4), Some(8)]);
+    }
 
     #[test]
     fn test_sort_comparator() {
@@ -44,6 +44,330 @@ fn test_samples() {
             ],
             "testdata/out/names0.txt",
         ),
+        (vec!["testdata/in/quoted.csv"], "testdata/out/quoted0.txt"),
+        (
+            vec!["-D", "-r", "-d", ": ?", "testdata/in/spaced.txt"],
+            "testdata/out/spaced0.txt",
+        ),
+        (
+            vec!["--max-columns", "4", "-l", "l|r|r|l", "testdata/in/ps.txt"],
+            "testdata/out/ps0.txt",
+        ),
+        (
+            vec![
+                "--pattern",
+                r#"^(?P<ip>\S+) \S+ \S+ \[(?P<time>[^]]+)\] "(?P<request>[^"]*)" (?P<status>\d+)"#,
+                "-s",
+                "--sort-by",
+                "3N",
+                "-l",
+                "l|l|l|r",
+                "testdata/in/access.log",
+            ],
+            "testdata/out/access0.txt",
+        ),
+        (
+            vec!["-i", "json", "testdata/in/services.json"],
+            "testdata/out/services0.txt",
+        ),
+        (
+            vec![
+                "-i",
+                "json",
+                "--json-arrays",
+                "index",
+                "testdata/in/services.json",
+            ],
+            "testdata/out/services1.txt",
+        ),
+        (
+            vec!["-i", "md", "testdata/in/planets.md"],
+            "testdata/out/planets0.txt",
+        ),
+        (
+            vec!["-i", "fw", "-l", "l|r|r|r|r|l", "testdata/in/df.txt"],
+            "testdata/out/df0.txt",
+        ),
+        (
+            vec!["-i", "logfmt", "-c", "1,2,4,5", "testdata/in/app.logfmt"],
+            "testdata/out/app0.txt",
+        ),
+        (
+            vec!["-i", "records", "-c", "0,2,1", "testdata/in/packages.txt"],
+            "testdata/out/packages0.txt",
+        ),
+        (
+            vec!["--encoding", "latin1", "testdata/in/staedte.csv"],
+            "testdata/out/staedte0.txt",
+        ),
+        (vec!["testdata/in/windows.csv"], "testdata/out/windows0.txt"),
+        (
+            vec![
+                "--skip-lines",
+                "2",
+                "--skip-empty",
+                "--comment",
+                "#",
+                "--header-line",
+                "0",
+                "-s",
+                "--sort-by",
+                "2N",
+                "-l",
+                "l|r|r",
+                "testdata/in/report.csv",
+            ],
+            "testdata/out/report0.txt",
+        ),
+        (
+            vec![
+                "--source-column",
+                "--skip-empty",
+                "testdata/in/sales-jan.csv",
+                "testdata/in/sales-feb.csv",
+            ],
+            "testdata/out/sales0.txt",
+        ),
+        (
+            vec![
+                "-o",
+                "html",
+                "-l",
+                "l|c|r",
+                "-c",
+                "0,1,2",
+                "testdata/in/constants.csv",
+            ],
+            "testdata/out/constants0.txt",
+        ),
+        (
+            vec!["-o", "csv", "-c", "2,0", "testdata/in/quoted.csv"],
+            "testdata/out/quoted1.txt",
+        ),
+        (
+            vec![
+                "-o",
+                "tsv",
+                "-c",
+                "2,0",
+                "-s",
+                "--sort-by",
+                "2l",
+                "--sort-ignore-first",
+                "testdata/in/hosts.tsv",
+            ],
+            "testdata/out/hosts1.txt",
+        ),
+        (
+            vec![
+                "-o",
+                "json",
+                "--infer-types",
+                "-s",
+                "--sort-by",
+                "0N",
+                "--sort-ignore-first",
+                "testdata/in/numbers.csv",
+            ],
+            "testdata/out/numbers2.txt",
+        ),
+        (
+            vec![
+                "-o",
+                "ndjson",
+                "-t",
+                "id,first,last",
+                "-c",
+                "0,2,3",
+                "testdata/in/users.csv",
+            ],
+            "testdata/out/users1.txt",
+        ),
+        (
+            vec![
+                "-o",
+                "latex",
+                "-l",
+                "l|c|r",
+                "-c",
+                "0,1,2",
+                "--decoration",
+                "full",
+                "testdata/in/constants.csv",
+            ],
+            "testdata/out/constants1.txt",
+        ),
+        (
+            vec!["-o", "rst", "testdata/in/quoted.csv"],
+            "testdata/out/quoted2.txt",
+        ),
+        (
+            vec!["-o", "rst-simple", "testdata/in/hosts.tsv"],
+            "testdata/out/hosts2.txt",
+        ),
+        (
+            vec!["-i", "md", "-o", "adoc", "testdata/in/planets.md"],
+            "testdata/out/planets2.txt",
+        ),
+        (
+            vec![
+                "-o",
+                "org",
+                "-c",
+                "0,2,1",
+                "-s",
+                "--sort-by",
+                "0l",
+                "--sort-ignore-first",
+                "testdata/in/constants.csv",
+            ],
+            "testdata/out/constants2.txt",
+        ),
+        (
+            vec![
+                "-o",
+                "sql",
+                "--table-name",
+                "numbers",
+                "--batch-size",
+                "5",
+                "testdata/in/numbers.csv",
+            ],
+            "testdata/out/numbers3.txt",
+        ),
+        (
+            vec![
+                "--theme",
+                "rounded",
+                "--decoration",
+                "full",
+                "-l",
+                "l | r",
+                "testdata/in/hosts.tsv",
+            ],
+            "testdata/out/hosts3.txt",
+        ),
+        (
+            vec!["--theme", "psql", "-l", "r|l|c", "testdata/in/numbers.csv"],
+            "testdata/out/numbers4.txt",
+        ),
+        (
+            vec![
+                "--theme",
+                "double",
+                "-a",
+                "--decoration",
+                "full",
+                "testdata/in/hosts.tsv",
+            ],
+            "testdata/out/hosts4.txt",
+        ),
+        (
+            vec![
+                "--border-chars",
+                "┌┬┐╞╪╡└┴┘─═│",
+                "--decoration",
+                "full",
+                "--padding",
+                "-c",
+                "0,2",
+                "testdata/in/hosts.tsv",
+            ],
+            "testdata/out/hosts5.txt",
+        ),
+        (
+            vec![
+                "-t",
+                "A|B,C,D",
+                "-a",
+                "--decoration",
+                "full",
+                "testdata/in/hosts.tsv",
+            ],
+            "testdata/out/hosts6.txt",
+        ),
+        (
+            vec![
+                "-w",
+                "3c,4e,6c",
+                "-l",
+                "l | r  |c",
+                "--decoration",
+                "full",
+                "testdata/in/hosts.tsv",
+            ],
+            "testdata/out/hosts7.txt",
+        ),
+        (
+            vec![
+                "-i",
+                "json",
+                "-s",
+                "--sort-by",
+                "1L",
+                "--sort-ignore-first",
+                "testdata/in/services.json",
+            ],
+            "testdata/out/services2.txt",
+        ),
+        (
+            vec![
+                "-s",
+                "--sort-by",
+                "0L",
+                "--sort-ignore-first",
+                "--sort-by-output",
+                "--skip-empty",
+                "testdata/in/sales-jan.csv",
+                "testdata/in/sales-feb.csv",
+            ],
+            "testdata/out/sales1.txt",
+        ),
+        (
+            vec![
+                "--source-column",
+                "-t",
+                "Host,Address",
+                "testdata/in/hosts.tsv",
+            ],
+            "testdata/out/hosts8.txt",
+        ),
+        (
+            vec!["-i", "md", "--source-column", "testdata/in/planets.md"],
+            "testdata/out/planets3.txt",
+        ),
+        (
+            vec![
+                "-i",
+                "md",
+                "testdata/in/planets.md",
+                "testdata/in/dwarfs.md",
+            ],
+            "testdata/out/planets4.txt",
+        ),
+        (
+            vec!["--decoration", "full", "testdata/in/wrapped.csv"],
+            "testdata/out/wrapped0.txt",
+        ),
+        (
+            vec!["-i", "md", "-o", "md", "testdata/in/planets.md"],
+            "testdata/out/planets1.txt",
+        ),
+        (
+            vec![
+                "-o",
+                "md",
+                "-c",
+                "3,0,2",
+                "-s",
+                "--sort-by",
+                "1n",
+                "--sort-ignore-first",
+                "-u",
+                "testdata/in/numbers.csv",
+            ],
+            "testdata/out/numbers1.txt",
+        ),
     ];
     for (c, r) in tests {
         let out = Command::new(P).args(c).output().expect("test failed");
@@ -55,3 +379,43 @@ fn test_samples() {
         );
     }
 }
+
+#[test]
+fn test_delimiter_detection() {
+    let out = Command::new(P)
+        .args(["-v", "testdata/in/hosts.tsv"])
+        .output()
+        .expect("test failed");
+    assert_eq!(
+        String::from_utf8(out.stderr).unwrap(),
+        "Detected delimiter: tab\n"
+    );
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        fs::read_to_string("testdata/out/hosts0.txt").unwrap()
+    );
+}
+
+#[test]
+fn test_malformed_ndjson() {
+    let tests = vec![
+        (vec![], "testdata/out/events0.txt"),
+        (vec!["--skip-lines", "1"], "testdata/out/events1.txt"),
+    ];
+    for (args, expected) in tests {
+        let out = Command::new(P)
+            .args(["-i", "ndjson"])
+            .args(args)
+            .arg("testdata/in/events.ndjson")
+            .output()
+            .expect("test failed");
+        assert_eq!(
+            String::from_utf8(out.stderr).unwrap(),
+            "Line 3: EOF while parsing an object at line 1 column 71\n"
+        );
+        assert_eq!(
+            String::from_utf8(out.stdout).unwrap(),
+            fs::read_to_string(expected).unwrap()
+        );
+    }
+}